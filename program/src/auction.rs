@@ -0,0 +1,759 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::{Pack, Sealed},
+    pubkey::Pubkey,
+};
+use spl_token::instruction::initialize_account2;
+
+use crate::{
+    error::NameAuctionError,
+    utils::{check_account_key, check_account_owner, Cpi},
+};
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Bid {
+    pub bidder: [u8; 32],
+    pub amount: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuctionMode {
+    English,
+    SealedBid, // Bidders commit to a hashed bid and reveal it once bidding is over
+}
+
+/// Auction of a name, bids and deposits are held in the pot token account of the auction
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Auction {
+    pub resource: [u8; 32], // The name account
+    pub token_mint: [u8; 32],
+    pub mode: AuctionMode,
+    pub reveal_length: i64,    // In seconds, zero for English auctions
+    pub open_commitments: u64, // Sealed bids that have not been refunded yet
+    pub minimum_price: u64,
+    pub max_price: Option<u64>, // Buy now price
+    pub length: i64,            // In seconds
+    pub end_auction_gap: i64,   // In seconds
+    pub bid_increment_bps: u64, // Minimum raise over the top bid, in basis points
+    pub end_auction_at: i64,    // Unix timestamp, extended by late bids
+    pub ended_at: Option<i64>,  // Set when the auction ends early
+    pub last_bid: Option<i64>,
+    pub top_bid: Option<Bid>,
+    pub settled: bool,                        // The top bid has been paid out
+    pub second_price: bool, // The winner pays the second highest bid plus one increment
+    pub second_bid: Option<u64>, // Amount of the second highest bid
+    pub reserve_commitment: Option<[u8; 32]>, // Hash of the hidden reserve of the seller
+    pub reserve_price: Option<u64>, // Set once the hidden reserve is revealed
+    pub accepted: bool,     // The seller accepted the top bid below the reserve
+}
+
+impl Auction {
+    pub const SEED: &'static [u8] = b"auction";
+    pub const POT_SEED: &'static [u8] = b"auction_pot";
+
+    pub fn new(
+        resource: &Pubkey,
+        token_mint: &Pubkey,
+        minimum_price: u64,
+        max_price: Option<u64>,
+        length: i64,
+        end_auction_gap: i64,
+        bid_increment_bps: u64,
+    ) -> Self {
+        Self {
+            resource: resource.to_bytes(),
+            token_mint: token_mint.to_bytes(),
+            mode: AuctionMode::English,
+            reveal_length: 0,
+            open_commitments: 0,
+            minimum_price,
+            max_price,
+            length,
+            end_auction_gap,
+            bid_increment_bps,
+            end_auction_at: 0,
+            ended_at: None,
+            last_bid: None,
+            top_bid: None,
+            settled: false,
+            second_price: false,
+            second_bid: None,
+            reserve_commitment: None,
+            reserve_price: None,
+            accepted: false,
+        }
+    }
+
+    pub fn find_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &name_account.to_bytes()], program_id)
+    }
+
+    pub fn find_pot_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::POT_SEED, &name_account.to_bytes()], program_id)
+    }
+
+    pub fn from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+        name_account: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_account_key(account, &Self::find_key(program_id, name_account).0)?;
+        check_account_owner(account, program_id)?;
+        Self::unpack_from_slice(&account.data.borrow())
+    }
+
+    pub fn with_sealed_bids(mut self, reveal_length: i64) -> Self {
+        self.mode = AuctionMode::SealedBid;
+        self.reveal_length = reveal_length;
+        self
+    }
+
+    pub fn with_second_price(mut self) -> Self {
+        self.second_price = true;
+        self
+    }
+
+    pub fn with_hidden_reserve(mut self, reserve_commitment: [u8; 32]) -> Self {
+        self.reserve_commitment = Some(reserve_commitment);
+        self
+    }
+
+    pub fn reserve_commitment(name_account: &Pubkey, reserve: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&name_account.to_bytes(), &reserve.to_le_bytes(), salt]).to_bytes()
+    }
+
+    pub fn start(&mut self, current_timestamp: i64) -> ProgramResult {
+        // Deposits of the previous sealed bids are tied to the previous round
+        if self.open_commitments != 0 {
+            msg!("The sealed bids of the previous auction have to be refunded first");
+            return Err(NameAuctionError::AuctionInProgress.into());
+        }
+        self.end_auction_at = current_timestamp + self.length;
+        self.ended_at = None;
+        self.last_bid = None;
+        self.top_bid = None;
+        self.settled = false;
+        self.second_bid = None;
+        self.reserve_price = None;
+        self.accepted = false;
+        Ok(())
+    }
+
+    /// Bids are no longer accepted, sealed bids can still be revealed until the auction has ended
+    pub fn bidding_ended(&self, current_timestamp: i64) -> bool {
+        self.ended_at.is_some() || current_timestamp >= self.end_auction_at
+    }
+
+    /// The end of the auction, including the reveal period of sealed bid auctions
+    pub fn end_time(&self) -> i64 {
+        self.ended_at
+            .unwrap_or(self.end_auction_at + self.reveal_length)
+    }
+
+    pub fn ended(&self, current_timestamp: i64) -> bool {
+        self.ended_at.is_some() || current_timestamp >= self.end_time()
+    }
+
+    fn raise(&self, amount: u64) -> u64 {
+        let increment = (amount as u128) * (self.bid_increment_bps as u128) / 10_000;
+        amount.saturating_add((increment as u64).max(1))
+    }
+
+    pub fn minimum_bid(&self) -> u64 {
+        match &self.top_bid {
+            Some(bid) => self.raise(bid.amount),
+            None => self.minimum_price,
+        }
+    }
+
+    /// The amount paid by the winner, never more than its own bid
+    pub fn clearing_price(&self) -> Option<u64> {
+        let top = self.top_bid.as_ref()?.amount;
+        if !self.second_price || self.accepted {
+            return Some(top);
+        }
+        let price = match self.second_bid {
+            Some(second) => self.raise(second),
+            None => self.minimum_price,
+        };
+        Some(price.max(self.reserve_price.unwrap_or(0)).min(top))
+    }
+
+    pub fn reveal_reserve(
+        &mut self,
+        reserve: u64,
+        salt: &[u8; 32],
+        current_timestamp: i64,
+        reveal_length: i64,
+    ) -> ProgramResult {
+        let commitment = match self.reserve_commitment {
+            Some(commitment) => commitment,
+            None => {
+                msg!("The auction has no hidden reserve");
+                return Err(ProgramError::InvalidArgument);
+            }
+        };
+        if !self.ended(current_timestamp) {
+            msg!("The reserve can only be revealed once the auction has ended");
+            return Err(NameAuctionError::AuctionInProgress.into());
+        }
+        // Past the deadline the reserve counts as unmet, see `reserve_met`
+        if current_timestamp >= self.end_auction_at + reveal_length {
+            msg!("The reserve can no longer be revealed");
+            return Err(NameAuctionError::AuctionEnded.into());
+        }
+        if self.reserve_price.is_some() || self.settled {
+            msg!("The reserve has already been revealed");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if Self::reserve_commitment(&Pubkey::new(&self.resource), reserve, salt) != commitment {
+            msg!("The revealed reserve does not match the commitment");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.reserve_price = Some(reserve);
+        Ok(())
+    }
+
+    /// Whether the top bid meets the hidden reserve, which counts as unmet when the seller
+    /// does not reveal it in time
+    pub fn reserve_met(
+        &self,
+        current_timestamp: i64,
+        reveal_length: i64,
+    ) -> Result<bool, ProgramError> {
+        let top = match &self.top_bid {
+            Some(bid) => bid.amount,
+            None => return Ok(false),
+        };
+        if self.accepted {
+            return Ok(true);
+        }
+        match (self.reserve_commitment, self.reserve_price) {
+            (None, _) => Ok(true),
+            (Some(_), Some(reserve)) => Ok(top >= reserve),
+            (Some(_), None) if current_timestamp < self.end_auction_at + reveal_length => {
+                msg!("The seller can still reveal the reserve");
+                Err(NameAuctionError::AuctionInProgress.into())
+            }
+            (Some(_), None) => Ok(false),
+        }
+    }
+
+    /// The seller can settle at the top bid when it does not meet the reserve
+    pub fn accept_bid(
+        &mut self,
+        current_timestamp: i64,
+        reveal_length: i64,
+        accept_bid_window: i64,
+    ) -> ProgramResult {
+        if !self.ended(current_timestamp) {
+            msg!("The auction has to end before a bid can be accepted");
+            return Err(NameAuctionError::AuctionInProgress.into());
+        }
+        if current_timestamp >= self.end_auction_at + accept_bid_window {
+            msg!("The top bid can no longer be accepted");
+            return Err(NameAuctionError::AuctionEnded.into());
+        }
+        if self.top_bid.is_none() || self.settled || self.accepted {
+            msg!("There is no bid to accept");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.reserve_commitment.is_none()
+            || matches!(self.reserve_met(current_timestamp, reveal_length), Ok(true))
+        {
+            msg!("The top bid already meets the reserve");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.accepted = true;
+        Ok(())
+    }
+
+    /// Records a new top bid and returns the previous one, which has to be refunded
+    pub fn place_bid(
+        &mut self,
+        mut bid: Bid,
+        current_timestamp: i64,
+    ) -> Result<Option<Bid>, ProgramError> {
+        if self.mode != AuctionMode::English {
+            msg!("Bids on sealed bid auctions have to be committed");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.ended(current_timestamp) {
+            msg!("The auction has ended");
+            return Err(NameAuctionError::AuctionEnded.into());
+        }
+        if let Some(max_price) = self.max_price {
+            if bid.amount >= max_price {
+                msg!("Buying now");
+                bid.amount = max_price;
+                self.ended_at = Some(current_timestamp);
+            }
+        }
+        let minimum_bid = self.minimum_bid();
+        if bid.amount < minimum_bid && self.ended_at.is_none() {
+            msg!("The minimum bid is {}", minimum_bid);
+            return Err(NameAuctionError::BidTooLow.into());
+        }
+
+        // Late bids push the end of the auction to leave time for other bidders
+        if self.end_auction_at - current_timestamp < self.end_auction_gap {
+            self.end_auction_at = current_timestamp + self.end_auction_gap;
+        }
+        self.last_bid = Some(current_timestamp);
+        let outbid = self.top_bid.replace(bid);
+        if let Some(outbid) = &outbid {
+            self.second_bid = Some(outbid.amount);
+        }
+        Ok(outbid)
+    }
+
+    pub fn commit_bid(&mut self, current_timestamp: i64) -> ProgramResult {
+        if self.mode != AuctionMode::SealedBid {
+            msg!("Only sealed bid auctions take commitments");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.bidding_ended(current_timestamp) {
+            msg!("The bidding period has ended");
+            return Err(NameAuctionError::AuctionEnded.into());
+        }
+        self.open_commitments += 1;
+        self.last_bid = Some(current_timestamp);
+        Ok(())
+    }
+
+    /// Records a revealed bid, the highest valid reveal wins and ties go to the first one
+    pub fn reveal_bid(&mut self, bid: Bid, current_timestamp: i64) -> ProgramResult {
+        if !self.bidding_ended(current_timestamp) {
+            msg!("Bids can only be revealed once the bidding period is over");
+            return Err(NameAuctionError::AuctionInProgress.into());
+        }
+        if self.ended(current_timestamp) {
+            msg!("The reveal period has ended");
+            return Err(NameAuctionError::AuctionEnded.into());
+        }
+        if bid.amount < self.minimum_price {
+            msg!("The minimum bid is {}", self.minimum_price);
+            return Err(NameAuctionError::BidTooLow.into());
+        }
+        if matches!(&self.top_bid, Some(top) if top.amount >= bid.amount) {
+            msg!("A higher bid has already been revealed");
+            self.second_bid = self.second_bid.max(Some(bid.amount));
+            return Ok(());
+        }
+        if let Some(top) = self.top_bid.replace(bid) {
+            self.second_bid = Some(top.amount);
+        }
+        Ok(())
+    }
+}
+
+/// A sealed bid and the deposit escrowed in the auction pot to back it
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct SealedBid {
+    pub auction: [u8; 32],
+    pub bidder: [u8; 32],
+    pub commitment: [u8; 32],
+    pub deposit: u64,
+    pub revealed: bool,
+}
+
+impl SealedBid {
+    pub const SEED: &'static [u8] = b"sealed_bid";
+
+    pub fn find_key(program_id: &Pubkey, auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, &auction.to_bytes(), &bidder.to_bytes()],
+            program_id,
+        )
+    }
+
+    /// The bidder is part of the commitment so that it cannot be replayed by someone else
+    pub fn commitment(bidder: &Pubkey, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&bidder.to_bytes(), &amount.to_le_bytes(), salt]).to_bytes()
+    }
+
+    pub fn from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+        auction: &Pubkey,
+        bidder: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_account_key(account, &Self::find_key(program_id, auction, bidder).0)?;
+        check_account_owner(account, program_id)?;
+        Self::unpack_from_slice(&account.data.borrow())
+    }
+}
+
+impl Sealed for SealedBid {}
+
+impl Pack for SealedBid {
+    const LEN: usize = 3 * 32 + 8 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+/// Tokens owed to a bidder by an auction, withdrawn from the auction pot with WithdrawRefund
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct BidRefund {
+    pub auction: [u8; 32],
+    pub bidder: [u8; 32],
+    pub amount: u64,
+}
+
+impl BidRefund {
+    pub const SEED: &'static [u8] = b"bid_refund";
+
+    pub fn find_key(program_id: &Pubkey, auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, &auction.to_bytes(), &bidder.to_bytes()],
+            program_id,
+        )
+    }
+
+    pub fn from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+        auction: &Pubkey,
+        bidder: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_account_key(account, &Self::find_key(program_id, auction, bidder).0)?;
+        check_account_owner(account, program_id)?;
+        Self::unpack_from_slice(&account.data.borrow())
+    }
+}
+
+impl Sealed for BidRefund {}
+
+impl Pack for BidRefund {
+    const LEN: usize = 2 * 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+impl Sealed for Auction {}
+
+impl Pack for Auction {
+    const LEN: usize =
+        32 + 32 + 1 + 8 + 8 + 8 + 9 + 8 + 8 + 8 + 8 + 9 + 9 + 41 + 1 + 1 + 9 + 33 + 9 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+// The auction and its pot are created once per name and reused by the following auctions
+#[allow(clippy::too_many_arguments)]
+pub fn create_auction_accounts<'a>(
+    program_id: &Pubkey,
+    system_program: &AccountInfo<'a>,
+    spl_token_program: &AccountInfo<'a>,
+    rent_sysvar: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    auction: &AccountInfo<'a>,
+    pot: &AccountInfo<'a>,
+    quote_mint: &AccountInfo<'a>,
+    central_state: &AccountInfo<'a>,
+    name_account: &Pubkey,
+) -> ProgramResult {
+    let (auction_key, auction_nonce) = Auction::find_key(program_id, name_account);
+    check_account_key(auction, &auction_key)?;
+    let (pot_key, pot_nonce) = Auction::find_pot_key(program_id, name_account);
+    check_account_key(pot, &pot_key)?;
+
+    if auction.data_is_empty() {
+        Cpi::create_account(
+            program_id,
+            system_program,
+            fee_payer,
+            auction,
+            rent_sysvar,
+            &[Auction::SEED, &name_account.to_bytes(), &[auction_nonce]],
+            Auction::LEN,
+        )?;
+    }
+    if pot.data_is_empty() {
+        Cpi::create_account(
+            &spl_token::ID,
+            system_program,
+            fee_payer,
+            pot,
+            rent_sysvar,
+            &[Auction::POT_SEED, &name_account.to_bytes(), &[pot_nonce]],
+            spl_token::state::Account::LEN,
+        )?;
+        let init_pot_instruction =
+            initialize_account2(&spl_token::ID, pot.key, quote_mint.key, central_state.key)?;
+        invoke(
+            &init_pot_instruction,
+            &[
+                spl_token_program.clone(),
+                pot.clone(),
+                quote_mint.clone(),
+                rent_sysvar.clone(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+// Refunds are credited to the refund account of the bidder rather than transferred, so that no
+// bidder can block an instruction by closing its token account. The refund account is created
+// the first time the bidder is owed something.
+#[allow(clippy::too_many_arguments)]
+pub fn credit_refund<'a>(
+    program_id: &Pubkey,
+    system_program: &AccountInfo<'a>,
+    rent_sysvar: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    bid_refund: &AccountInfo<'a>,
+    auction: &Pubkey,
+    bidder: &Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let (bid_refund_key, bid_refund_nonce) = BidRefund::find_key(program_id, auction, bidder);
+    check_account_key(bid_refund, &bid_refund_key)?;
+
+    let mut refund = if bid_refund.data_is_empty() {
+        Cpi::create_account(
+            program_id,
+            system_program,
+            fee_payer,
+            bid_refund,
+            rent_sysvar,
+            &[
+                BidRefund::SEED,
+                &auction.to_bytes(),
+                &bidder.to_bytes(),
+                &[bid_refund_nonce],
+            ],
+            BidRefund::LEN,
+        )?;
+        BidRefund {
+            auction: auction.to_bytes(),
+            bidder: bidder.to_bytes(),
+            amount: 0,
+        }
+    } else {
+        BidRefund::from_account_info(bid_refund, program_id, auction, bidder)?
+    };
+    refund.amount = refund
+        .amount
+        .checked_add(amount)
+        .ok_or(ProgramError::InvalidArgument)?;
+    refund.pack_into_slice(&mut bid_refund.data.borrow_mut());
+    Ok(())
+}
+
+// Auctions can only be set up again once the previous one is over and paid out
+pub fn check_auction_reusable(auction: &AccountInfo, current_timestamp: i64) -> ProgramResult {
+    if auction.data_is_empty() {
+        return Ok(());
+    }
+    let previous = Auction::unpack_from_slice(&auction.data.borrow())?;
+    if !previous.ended(current_timestamp) {
+        msg!("The previous auction of this name is still in progress");
+        return Err(NameAuctionError::AuctionInProgress.into());
+    }
+    if previous.top_bid.is_some() && !previous.settled {
+        msg!("The previous auction of this name has to be claimed first");
+        return Err(NameAuctionError::AuctionRealized.into());
+    }
+    if previous.open_commitments != 0 {
+        msg!("The sealed bids of the previous auction have to be refunded first");
+        return Err(NameAuctionError::AuctionInProgress.into());
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_auction() {
+    let name = Pubkey::new_unique();
+    let mut auction = Auction::new(
+        &name,
+        &Pubkey::new_unique(),
+        100,
+        Some(1_000),
+        3_600,
+        600,
+        100,
+    );
+    auction.start(0).unwrap();
+    let bid = |amount: u64| Bid {
+        bidder: Pubkey::new_unique().to_bytes(),
+        amount,
+    };
+
+    assert_eq!(
+        auction.place_bid(bid(99), 10).unwrap_err(),
+        NameAuctionError::BidTooLow.into()
+    );
+    assert_eq!(auction.place_bid(bid(100), 10).unwrap(), None);
+    // Bids must raise the top bid by the increment
+    assert!(auction.place_bid(bid(100), 20).is_err());
+
+    // Late bids extend the auction
+    let outbid = auction.place_bid(bid(200), 3_500).unwrap().unwrap();
+    assert_eq!(outbid.amount, 100);
+    assert_eq!(auction.end_auction_at, 4_100);
+    assert!(!auction.ended(4_000));
+
+    // Bids above the buy now price end the auction at that price
+    auction.place_bid(bid(5_000), 4_050).unwrap();
+    assert_eq!(auction.top_bid.as_ref().unwrap().amount, 1_000);
+    assert!(auction.ended(4_050));
+    assert_eq!(
+        auction.place_bid(bid(2_000), 4_060).unwrap_err(),
+        NameAuctionError::AuctionEnded.into()
+    );
+
+    let mut data = vec![0; Auction::LEN];
+    auction.pack_into_slice(&mut data);
+    assert_eq!(Auction::unpack_from_slice(&data).unwrap(), auction);
+}
+
+#[test]
+pub fn test_sealed_bid_auction() {
+    let name = Pubkey::new_unique();
+    let mut auction = Auction::new(&name, &Pubkey::new_unique(), 100, None, 3_600, 600, 100)
+        .with_sealed_bids(600);
+    auction.start(0).unwrap();
+    let bid = |amount: u64| Bid {
+        bidder: Pubkey::new_unique().to_bytes(),
+        amount,
+    };
+
+    assert!(auction.place_bid(bid(200), 10).is_err());
+    auction.commit_bid(10).unwrap();
+    auction.commit_bid(3_599).unwrap();
+    assert_eq!(auction.open_commitments, 2);
+
+    // Reveals only happen between the end of the bidding period and the end of the auction
+    assert!(auction.reveal_bid(bid(300), 3_500).is_err());
+    assert!(auction.commit_bid(3_600).is_err());
+    assert!(!auction.ended(3_600));
+    assert!(auction.reveal_bid(bid(99), 3_600).is_err());
+    auction.reveal_bid(bid(300), 3_700).unwrap();
+    auction.reveal_bid(bid(200), 3_800).unwrap();
+    auction.reveal_bid(bid(300), 3_900).unwrap();
+    assert_eq!(auction.top_bid.as_ref().unwrap().amount, 300);
+    assert_eq!(auction.second_bid, Some(300));
+    assert_eq!(auction.clearing_price(), Some(300));
+    assert!(auction.ended(4_200));
+    assert_eq!(auction.end_time(), 4_200);
+    assert!(auction.reveal_bid(bid(400), 4_200).is_err());
+
+    // Deposits have to be refunded before the auction can be restarted
+    assert!(auction.start(5_000).is_err());
+    auction.open_commitments = 0;
+    auction.start(5_000).unwrap();
+
+    let mut data = vec![0; Auction::LEN];
+    auction.pack_into_slice(&mut data);
+    assert_eq!(Auction::unpack_from_slice(&data).unwrap(), auction);
+}
+
+#[test]
+pub fn test_second_price() {
+    let name = Pubkey::new_unique();
+    let mut auction =
+        Auction::new(&name, &Pubkey::new_unique(), 100, None, 3_600, 600, 100).with_second_price();
+    auction.start(0).unwrap();
+    let bid = |amount: u64| Bid {
+        bidder: Pubkey::new_unique().to_bytes(),
+        amount,
+    };
+
+    // A single bidder pays the minimum price
+    auction.place_bid(bid(500), 10).unwrap();
+    assert_eq!(auction.clearing_price(), Some(100));
+
+    // Otherwise the second bid plus one increment
+    auction.place_bid(bid(1_000), 20).unwrap();
+    assert_eq!(auction.second_bid, Some(500));
+    assert_eq!(auction.clearing_price(), Some(505));
+
+    // Sealed bids are ranked on reveal
+    let mut auction = Auction::new(&name, &Pubkey::new_unique(), 100, None, 3_600, 600, 100)
+        .with_sealed_bids(600)
+        .with_second_price();
+    auction.start(0).unwrap();
+    auction.reveal_bid(bid(200), 3_600).unwrap();
+    auction.reveal_bid(bid(700), 3_700).unwrap();
+    auction.reveal_bid(bid(400), 3_800).unwrap();
+    assert_eq!(auction.top_bid.as_ref().unwrap().amount, 700);
+    assert_eq!(auction.clearing_price(), Some(404));
+}
+
+#[test]
+pub fn test_hidden_reserve() {
+    let name = Pubkey::new_unique();
+    let salt = [7; 32];
+    let mut auction = Auction::new(&name, &Pubkey::new_unique(), 10, None, 3_600, 600, 100)
+        .with_hidden_reserve(Auction::reserve_commitment(&name, 1_000, &salt));
+    auction.start(0).unwrap();
+    let bid = |amount: u64| Bid {
+        bidder: Pubkey::new_unique().to_bytes(),
+        amount,
+    };
+
+    // Bids below the reserve are accepted
+    auction.place_bid(bid(500), 10).unwrap();
+    assert!(auction.reveal_reserve(1_000, &salt, 100, 600).is_err());
+    assert!(auction.reserve_met(3_700, 600).is_err());
+    assert!(!auction.reserve_met(4_200, 600).unwrap());
+
+    assert!(auction.reveal_reserve(999, &salt, 3_700, 600).is_err());
+    // The reserve cannot be revealed once it counts as unmet
+    assert_eq!(
+        auction
+            .reveal_reserve(1_000, &salt, 4_200, 600)
+            .unwrap_err(),
+        NameAuctionError::AuctionEnded.into()
+    );
+    auction.reveal_reserve(1_000, &salt, 3_700, 600).unwrap();
+    assert!(!auction.reserve_met(3_700, 600).unwrap());
+
+    // The seller can still settle at the top bid
+    assert!(auction.accept_bid(4_200, 600, 600).is_err());
+    auction.accept_bid(3_800, 600, 600).unwrap();
+    assert!(auction.reserve_met(3_800, 600).unwrap());
+    assert_eq!(auction.clearing_price(), Some(500));
+
+    auction.start(5_000).unwrap();
+    auction.place_bid(bid(1_200), 5_010).unwrap();
+    auction.reveal_reserve(1_000, &salt, 8_700, 600).unwrap();
+    assert!(auction.reserve_met(8_700, 600).unwrap());
+    assert!(auction.accept_bid(8_700, 600, 600).is_err());
+    assert_eq!(auction.clearing_price(), Some(1_200));
+}
@@ -32,6 +32,32 @@ impl PrintProgramError for NameAuctionError {
             NameAuctionError::AuctionRealized => {
                 msg!("Error: The auction has already been run successfully")
             }
+            NameAuctionError::ProposalAlreadyExecuted => {
+                msg!("Error: The proposal has already been executed")
+            }
+            NameAuctionError::ThresholdNotMet => {
+                msg!("Error: The proposal does not have enough approvals")
+            }
+            NameAuctionError::Paused => msg!("Error: This instruction is paused"),
+            NameAuctionError::BlockedName => {
+                msg!("Error: This name is blocked and cannot be registered")
+            }
+            NameAuctionError::InvalidPremiumProof => msg!("Error: Invalid premium price proof"),
+            NameAuctionError::StaleOracle => msg!("Error: The oracle price is stale"),
+            NameAuctionError::OracleConfidenceTooWide => {
+                msg!("Error: The oracle confidence interval is too wide")
+            }
+            NameAuctionError::RegistrationExpired => {
+                msg!("Error: The registration of this name is past its grace period")
+            }
+            NameAuctionError::RegistrationNotExpired => {
+                msg!("Error: The registration of this name is not past its grace period")
+            }
+            NameAuctionError::InvalidVoucher => msg!("Error: Invalid voucher"),
+            NameAuctionError::VoucherAlreadyUsed => {
+                msg!("Error: The voucher has already been used")
+            }
+            NameAuctionError::AuctionEnded => msg!("Error: The auction has ended"),
         }
     }
 }
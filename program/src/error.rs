@@ -10,6 +10,30 @@ pub enum NameAuctionError {
     BidTooLow,
     #[error("The auction has already been run successfully")]
     AuctionRealized,
+    #[error("The proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[error("The proposal does not have enough approvals")]
+    ThresholdNotMet,
+    #[error("This instruction is paused")]
+    Paused,
+    #[error("This name is blocked and cannot be registered")]
+    BlockedName,
+    #[error("Invalid premium price proof")]
+    InvalidPremiumProof,
+    #[error("The oracle price is stale")]
+    StaleOracle,
+    #[error("The oracle confidence interval is too wide")]
+    OracleConfidenceTooWide,
+    #[error("The registration of this name is past its grace period")]
+    RegistrationExpired,
+    #[error("The registration of this name is not past its grace period")]
+    RegistrationNotExpired,
+    #[error("Invalid voucher")]
+    InvalidVoucher,
+    #[error("The voucher has already been used")]
+    VoucherAlreadyUsed,
+    #[error("The auction has ended")]
+    AuctionEnded,
 }
 
 impl From<NameAuctionError> for ProgramError {
@@ -1,19 +1,29 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    hash::hashv,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     system_program, sysvar,
 };
 
+use spl_name_service::state::{get_seeds_and_key, HASH_PREFIX};
+
 pub use crate::processor::create_admin;
-use crate::processor::{
-    BONFIDA_FIDA_VAULT, BONFIDA_SOL_VAULT, BONFIDA_USDC_VAULT, CENTRAL_STATE, PYTH_FIDA_PRICE_ACC,
-    ROOT_DOMAIN_ACCOUNT, USDC_MINT,
+use crate::{
+    auction::{Auction, BidRefund, SealedBid},
+    processor::ROOT_DOMAIN_ACCOUNT,
+    state::{
+        AdminAction, AdminSet, AuthorityRole, BlocklistPage, PaymentMint, PremiumProof,
+        PriceSchedule, ProgramConfig, Proposal, ReferralStats, Registration, Reservation,
+        TakeBackReason, TakeBackRecord, UsedVoucher, Voucher, PAUSE_CLAIM, PAUSE_CREATE,
+        PAUSE_CREATE_REVERSE, PAUSE_CREATE_V2, PAUSE_END_AUCTION, PAUSE_PLACE_BID,
+        PAUSE_RECLAIM_EXPIRED, PAUSE_RENEW, PAUSE_RESELL,
+    },
 };
 
 #[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
 pub enum ProgramInstruction {
-    /// Ihnitiates the central state of the auction program
+    /// Initiates the central state of the auction program
     ///
     /// Accounts expected by this instruction:
     ///
@@ -23,6 +33,10 @@ pub enum ProgramInstruction {
     ///   4. `[]` The sysvar rent account
     Init {
         state_nonce: u8,
+        admin: [u8; 32],
+        admin_claim_key: [u8; 32],
+        admin_create_key: [u8; 32],
+        config: ProgramConfig,
     },
     /// Creates an auction
     ///
@@ -35,18 +49,23 @@ pub enum ProgramInstruction {
     ///   5. `[]` The name account
     ///   6. `[writable]` The reverse lookup account
     ///   7. `[]` The system program account
-    ///   8. `[]` The auction program account
+    ///   8. `[]` The spl token program account
     ///   9. `[writable]` The auction account
     ///   10. `[]` The central state account
     ///   11. `[writable]` The state account
     ///   12. `[writable, signer]` The fee payer account
-    ///   13. `[writable]` The quote mint account
-    ///   14. `[writable]` The buy now account
-    ///   15. `[]` The Pyth Fida price account
+    ///   13. `[]` The quote mint account
+    ///   14. `[]` The Pyth Fida price account
+    ///   15. `[]` The blocklist page of the name
+    ///   16. `[]` The price schedule account
+    ///   17. `[]` The fallback Pyth Fida price account
+    ///   18. `[writable]` The auction pot token account
     Create {
         name: String,
+        sealed_bid: bool, // Bids are committed then revealed, only used when the auction is set up
+        second_price: bool, // The winner pays the second highest bid, only used when the auction is set up
     },
-    /// Executes an arbitrary program instruction, signing as the tokenized authority
+    /// Settles an ended auction: the name goes to the winner and the top bid is paid out of the pot
     ///
     /// Accounts expected by this instruction:
     ///
@@ -54,27 +73,39 @@ pub enum ProgramInstruction {
     ///   2. `[]` The spl token program
     ///   3. `[]` The spl name service program
     ///   4. `[]` The root domain account
-    ///   5. `[]` The name account
+    ///   5. `[writable]` The name account
     ///   6. `[]` The system program
-    ///   7. `[]` The auction program
-    ///   8. `[writable]` The auction account
-    ///   9. `[]` The central state account
-    ///   10. `[writable]` The state account
-    ///   11. `[writable]` The reselling account
-    ///   12. `[writable, signer]` The fee payer account
-    ///   13. `[writable]` The quote mint account
-    ///   14. `[writable]` The payout destination token account
-    ///   15. `[signer]` The bidder wallet account
-    ///   16. `[writable]` The bidder pot account
-    ///   17. `[writable]` The bidder pot token account
-    ///   18. `[]` The bonfida vault account
-    ///   19. `[]` The fida discount account
-    ///   20. `[writable]` The buy now account
-    ///   21. `[writable]` The Bonfida SOL vault account
+    ///   7. `[writable]` The auction account
+    ///   8. `[]` The central state account
+    ///   9. `[writable]` The state account
+    ///   10. `[writable]` The reselling account
+    ///   11. `[writable, signer]` The fee payer account
+    ///   12. `[writable]` The payout destination token account
+    ///   13. `[signer]` The bidder wallet account
+    ///   14. `[writable]` The auction pot token account
+    ///   15. `[writable]` The bonfida vault account
+    ///   16. `[]` The fida discount account
+    ///   17. `[writable]` The registration account of the name
+    ///   18. `[]` The rent sysvar account
+    ///   19. `[writable]` The bid refund account of the top bidder, credited with what it bid above
+    ///       the clearing price (see `Auction::clearing_price`) or its whole bid when the reserve is unmet
+    ///   20. `[writable]` The referrer token account (optional)
+    ///   21. `[writable]` The referral stats account (optional, with the referrer)
     Claim {
         hashed_name: [u8; 32],
         space: u32,
     },
+    /// Restarts a primary auction (admin command)
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   1. `[]` The clock sysvar account
+    ///   2. `[writable]` The auction account
+    ///   3. `[]` The name account
+    ///   4. `[]` The state account
+    ///   5. `[]` The central state account
+    ///   6. `[]` The admin set account
+    ///   7. `[writable]` The approved proposal account
     ResetAuction,
     /// Creates a secondary auction for domain owners to resell their ownership
     ///
@@ -88,19 +119,24 @@ pub enum ProgramInstruction {
     ///   6. `[signer]` The name owner account
     ///   7. `[writable]` The reverse lookup account
     ///   8. `[]` The system program account
-    ///   9. `[]` The auction program account
+    ///   9. `[]` The spl token program account
     ///   10. `[writable]` The auction account
     ///   11. `[]` The central state account
     ///   12. `[writable]` The state account
     ///   13. `[writable]` The reselling state account
     ///   14. `[writable]` The destination token account
     ///   15. `[writable, signer]` The fee payer account
-    ///   16. `[writable]` The buy now account
+    ///   16. `[]` The quote mint account
+    ///   17. `[writable]` The auction pot token account
     Resell {
         name: String,
         minimum_price: u64,
-        end_auction_at: u64, // Unix timestamp
+        end_auction_at: u64, // Length of the auction in seconds
         max_price: Option<u64>,
+        end_auction_gap: Option<u64>, // Defaults to the configured end gap
+        bid_increment_bps: Option<u64>, // Defaults to the configured bid increment
+        second_price: bool,           // The winner pays the second highest bid plus one increment
+        reserve_commitment: Option<[u8; 32]>, // Hidden reserve, see `Auction::reserve_commitment`
     },
     /// Creates a reverse lookup name registry for a domain name
     ///
@@ -125,50 +161,71 @@ pub enum ProgramInstruction {
     // | Index | Writable | Signer | Description                      |
     // |-------|----------|--------|----------------------------------|
     // | 0     | ✅        | ✅      | The fee payer account            |
-    // | 1     | ✅        | ✅      | The admin account                |
-    // | 2     | ❌        | ❌      | The name service program account |
-    // | 3     | ❌        | ❌      | The root domain account          |
-    // | 4     | ✅        | ❌      | The name account                 |
-    // | 5     | ✅        | ❌      | The reverse lookup account       |
-    // | 6     | ❌        | ❌      | The central state account        |
-    // | 7     | ❌        | ❌      | The system program account       |
-    // | 8     | ❌        | ❌      | The rent sysvar account          |
+    // | 1     | ❌        | ❌      | The name service program account |
+    // | 2     | ❌        | ❌      | The root domain account          |
+    // | 3     | ✅        | ❌      | The name account                 |
+    // | 4     | ✅        | ❌      | The reverse lookup account       |
+    // | 5     | ❌        | ❌      | The central state account        |
+    // | 6     | ❌        | ❌      | The system program account       |
+    // | 7     | ❌        | ❌      | The rent sysvar account          |
+    // | 8     | ❌        | ❌      | The admin set account            |
+    // | 9     | ✅        | ❌      | The approved proposal account    |
+    // | 10    | ❌        | ❌      | The recipient of the name        |
+    // | 11    | ✅        | ❌      | The reservation account          |
     CreateAdmin(create_admin::Params),
     //
     // Admin instruction used to force the claim of a broken name
     //
+    /// Settle an ended primary auction to the owner chosen by an approved proposal, the winning bid
+    /// is paid out to Bonfida
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   1. `[]` The sysvar clock account
     ///   2. `[]` The spl token program
     ///   3. `[]` The spl name service program
     ///   4. `[]` The root domain account
-    ///   5. `[]` The name account
+    ///   5. `[writable]` The name account
     ///   6. `[]` The system program
-    ///   7. `[]` The auction program
-    ///   8. `[writable]` The auction account
-    ///   9. `[]` The central state account
-    ///   10. `[writable]` The state account
-    ///   11. `[writable]` The reselling account
-    ///   12. `[writable, signer]` The fee payer account
-    ///   13. `[writable]` The quote mint account
-    ///   14. `[writable]` The payout destination token account
-    ///   15. `[signer]` The bidder wallet account
-    ///   16. `[writable]` The bidder pot account
-    ///   17. `[writable]` The bidder pot token account
-    ///   18. `[]` The bonfida vault account
-    ///   19. `[]` The fida discount account
-    ///   20. `[writable]` The buy now account
-    ///   21. `[writable]` The Bonfida SOL vault account
-    ///   21. `[signer]` The claim admin
+    ///   7. `[writable]` The auction account
+    ///   8. `[]` The central state account
+    ///   9. `[]` The state account
+    ///   10. `[writable]` The auction pot token account
+    ///   11. `[writable]` The bonfida vault account
+    ///   12. `[]` The new name owner account
+    ///   13. `[writable, signer]` The fee payer account
+    ///   14. `[writable]` The registration account
+    ///   15. `[]` The rent sysvar account
+    ///   16. `[]` The admin set account
+    ///   17. `[writable]` The approved proposal account
+    ///   18. `[writable]` The bid refund account of the top bidder (only with a bid)
     ClaimAdmin {
         hashed_name: [u8; 32],
         space: u32,
     },
-    /// End a reselling auction
+    /// End a reselling auction and return the name to the seller. Auctions with a bid can be
+    /// cancelled too while bidding is open: the top bid is credited back to the top bidder and the
+    /// seller pays the configured cancellation penalty.
     ///
     /// Accounts expected by this instruction:
     ///
+    ///   1. `[]` The clock sysvar account
+    ///   2. `[]` The name service program account
+    ///   3. `[]` The root domain account
+    ///   4. `[writable]` The name account
+    ///   5. `[writable]` The auction account
+    ///   6. `[]` The central state account
+    ///   7. `[writable]` The state account
+    ///   8. `[writable, signer]` The seller account
+    ///   9. `[writable]` The reselling state account
+    ///   10. `[writable]` The destination token account of the seller
+    ///   11. `[writable]` The Bonfida SOL vault account
+    ///   12. `[]` The system program account
+    ///   13. `[]` The spl token program account (only with a bid)
+    ///   14. `[writable]` The auction pot token account (only with a bid)
+    ///   15. `[writable]` The bid refund account of the top bidder (only with a bid)
+    ///   16. `[writable]` The Bonfida FIDA vault account (only with a bid)
+    ///   17. `[]` The rent sysvar account (only with a bid)
     EndAuction {
         name: String,
     },
@@ -187,37 +244,418 @@ pub enum ProgramInstruction {
     /// | 6     | ❌        | ❌      | The central state account     |
     /// | 7     | ✅        | ✅      | The buyer account             |
     /// | 8     | ✅        | ❌      | The buyer token account       |
-    /// | 9     | ❌        | ❌      | The quote mint account        |
-    /// | 10    | ❌        | ❌      | The Pyth FIDA price account   |
-    /// | 11    | ✅        | ❌      | The FIDA vault account        |
-    /// | 12    | ❌        | ❌      | The SPL token program         |
+    /// | 9     | ✅        | ❌      | The payment mint vault        |
+    /// | 10    | ❌        | ❌      | The SPL token program         |
+    /// | 11    | ❌        | ❌      | The state account             |
+    /// | 12    | ❌        | ❌      | The blocklist page account    |
+    /// | 13    | ❌        | ❌      | The price schedule account    |
+    /// | 14    | ❌        | ❌      | The auction account           |
+    /// | 15    | ❌        | ❌      | The mint used for the payment |
+    /// | 16    | ❌        | ❌      | The payment mint account      |
+    /// | 17    | ❌        | ❌      | The Pyth price account        |
+    /// | 18    | ✅        | ❌      | The registration account      |
+    /// | 19    | ❌        | ❌      | The instructions sysvar       |
+    /// | 20    | ✅        | ❌      | The used voucher account      |
+    /// | 21    | ✅        | ❌      | The referrer token account    |
+    /// | 22    | ✅        | ❌      | The referral stats account    |
+    ///
+    /// The instructions sysvar and used voucher accounts are only expected with a voucher, which must be
+    /// signed by the admin in an Ed25519 program instruction placed just before this one.
+    /// The referrer accounts are optional and follow them when present
     CreateV2 {
         name: String,
         space: u32,
+        premium_proofs: Vec<PremiumProof>, // Premium list leaves of the name, or around it when it is not listed
+        voucher: Option<Voucher>,
     },
     /// Take back a domain name
     /// Accounts expected by this instruction
     ///
     /// | Index | Writable | Signer | Description                   |
     /// |-------|----------|--------|-------------------------------|
-    /// | 0     | ✅        | ✅      | The admin account             |
-    /// | 1     | ❌        | ❌      | The name service program id   |
+    /// | 0     | ❌        | ❌      | The name service program id   |
+    /// | 1     | ✅        | ❌      | The name account              |
+    /// | 2     | ❌        | ❌      | The central state account     |
+    /// | 3     | ❌        | ❌      | The class (Pubkey::default()) |
+    /// | 4     | ❌        | ❌      | The .sol TLD                  |
+    /// | 5     | ❌        | ❌      | The new owner account         |
+    /// | 6     | ❌        | ❌      | The admin set account         |
+    /// | 7     | ✅        | ❌      | The approved proposal account |
+    /// | 8     | ✅        | ❌      | The takeback record account   |
+    /// | 9     | ❌        | ❌      | The system program account    |
+    /// | 10    | ✅        | ✅      | The fee payer account         |
+    /// | 11    | ❌        | ❌      | The rent sysvar account       |
+    TakeBack {
+        reason: TakeBackReason,
+    },
+    /// Update the program configuration stored in the central state
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The central state account     |
+    /// | 1     | ❌        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The approved proposal account |
+    UpdateConfig {
+        config: ProgramConfig,
+    },
+    /// Register the admin set, or replace it with an approved proposal
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                              |
+    /// |-------|----------|--------|------------------------------------------|
+    /// | 0     | ❌        | ✅      | The admin account                        |
+    /// | 1     | ❌        | ❌      | The central state account                |
+    /// | 2     | ✅        | ❌      | The admin set account                    |
+    /// | 3     | ❌        | ❌      | The system program account               |
+    /// | 4     | ✅        | ✅      | The fee payer account                    |
+    /// | 5     | ❌        | ❌      | The rent sysvar account                  |
+    /// | 6     | ✅        | ❌      | The approved proposal account (optional) |
+    SetAdminSet {
+        admins: Vec<[u8; 32]>,
+        threshold: u8,
+    },
+    /// Propose an admin action to the admin set
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ✅      | The proposer account          |
+    /// | 1     | ✅        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The proposal account          |
+    /// | 3     | ❌        | ❌      | The system program account    |
+    /// | 4     | ✅        | ✅      | The fee payer account         |
+    /// | 5     | ❌        | ❌      | The rent sysvar account       |
+    CreateProposal {
+        action: AdminAction,
+    },
+    /// Approve an admin action
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ✅      | The approver account          |
+    /// | 1     | ❌        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The proposal account          |
+    ApproveProposal,
+    /// Propose a new key for an admin role, effective after the configured delay
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The central state account     |
+    /// | 1     | ❌        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The approved proposal account |
+    ProposeAuthority {
+        role: AuthorityRole,
+        new_key: [u8; 32],
+    },
+    /// Accept a pending admin role once its delay has elapsed
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ✅      | The new authority account     |
+    /// | 1     | ✅        | ❌      | The central state account     |
+    AcceptAuthority {
+        role: AuthorityRole,
+    },
+    /// Pause the instructions matching the given flags (see `state::PAUSE_*`)
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The central state account     |
+    /// | 1     | ❌        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The approved proposal account |
+    Pause {
+        flags: u64,
+    },
+    /// Resume the instructions matching the given flags
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The central state account     |
+    /// | 1     | ❌        | ❌      | The admin set account         |
+    /// | 2     | ✅        | ❌      | The approved proposal account |
+    Unpause {
+        flags: u64,
+    },
+    /// Add or remove hashed names from a blocklist page
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The central state account     |
+    /// | 1     | ✅        | ❌      | The blocklist page account    |
+    /// | 2     | ❌        | ❌      | The system program account    |
+    /// | 3     | ✅        | ✅      | The fee payer account         |
+    /// | 4     | ❌        | ❌      | The rent sysvar account       |
+    /// | 5     | ❌        | ❌      | The admin set account         |
+    /// | 6     | ✅        | ❌      | The approved proposal account |
+    UpdateBlocklist {
+        page: u8,
+        add: Vec<[u8; 32]>,
+        remove: Vec<[u8; 32]>,
+    },
+    /// Set the length based price schedule used by Create and CreateV2
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The central state account     |
+    /// | 1     | ✅        | ❌      | The price schedule account    |
+    /// | 2     | ❌        | ❌      | The system program account    |
+    /// | 3     | ✅        | ✅      | The fee payer account         |
+    /// | 4     | ❌        | ❌      | The rent sysvar account       |
+    /// | 5     | ❌        | ❌      | The admin set account         |
+    /// | 6     | ✅        | ❌      | The approved proposal account |
+    SetPriceSchedule {
+        schedule: PriceSchedule,
+    },
+    /// Accept or update a mint for CreateV2 payments
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The central state account     |
+    /// | 1     | ✅        | ❌      | The payment mint account      |
+    /// | 2     | ❌        | ❌      | The system program account    |
+    /// | 3     | ✅        | ✅      | The fee payer account         |
+    /// | 4     | ❌        | ❌      | The rent sysvar account       |
+    /// | 5     | ❌        | ❌      | The admin set account         |
+    /// | 6     | ✅        | ❌      | The approved proposal account |
+    SetPaymentMint {
+        payment_mint: PaymentMint,
+    },
+    /// Extend the registration of a name by a number of years
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The name account              |
+    /// | 1     | ✅        | ❌      | The registration account      |
+    /// | 2     | ❌        | ❌      | The central state account     |
+    /// | 3     | ✅        | ✅      | The buyer account             |
+    /// | 4     | ✅        | ❌      | The buyer token account       |
+    /// | 5     | ✅        | ❌      | The payment mint vault        |
+    /// | 6     | ❌        | ❌      | The SPL token program         |
+    /// | 7     | ❌        | ❌      | The price schedule account    |
+    /// | 8     | ❌        | ❌      | The mint used for the payment |
+    /// | 9     | ❌        | ❌      | The payment mint account      |
+    /// | 10    | ❌        | ❌      | The Pyth price account        |
+    Renew {
+        name: String,
+        years: u8,
+    },
+    /// Transfer a name past its grace period to the central state. Reclaimed names are not put
+    /// back on sale automatically, the admins hand them out again with TakeBack.
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The naming service program ID |
+    /// | 1     | ❌        | ❌      | The root domain account       |
     /// | 2     | ✅        | ❌      | The name account              |
-    /// | 3     | ❌        | ❌      | The central state account     |
-    /// | 4     | ❌        | ❌      | The class (Pubkey::default()) |
-    /// | 5     | ❌        | ❌      | The .sol TLD                  |
-    TakeBack,
+    /// | 3     | ❌        | ❌      | The name class account        |
+    /// | 4     | ❌        | ❌      | The registration account      |
+    /// | 5     | ❌        | ❌      | The central state account     |
+    ReclaimExpired,
+    /// Create several names with a single payment
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The rent sysvar account       |
+    /// | 1     | ❌        | ❌      | The naming service program ID |
+    /// | 2     | ❌        | ❌      | The root domain account       |
+    /// | 3     | ❌        | ❌      | The system program account    |
+    /// | 4     | ❌        | ❌      | The central state account     |
+    /// | 5     | ✅        | ✅      | The buyer account             |
+    /// | 6     | ✅        | ❌      | The buyer token account       |
+    /// | 7     | ✅        | ❌      | The payment mint vault        |
+    /// | 8     | ❌        | ❌      | The SPL token program         |
+    /// | 9     | ❌        | ❌      | The price schedule account    |
+    /// | 10    | ❌        | ❌      | The mint used for the payment |
+    /// | 11    | ❌        | ❌      | The payment mint account      |
+    /// | 12    | ❌        | ❌      | The Pyth price account        |
+    ///
+    /// Followed for each name by
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The name account              |
+    /// | 1     | ✅        | ❌      | The reverse look up account   |
+    /// | 2     | ❌        | ❌      | The state account             |
+    /// | 3     | ❌        | ❌      | The blocklist page account    |
+    /// | 4     | ✅        | ❌      | The registration account      |
+    ///
+    /// Optionally followed by the referrer token account and its referral stats account, see CreateV2
+    CreateV2Batch {
+        names: Vec<(String, u32)>,              // Names and their space
+        premium_proofs: Vec<Vec<PremiumProof>>, // Premium proofs of each name, see CreateV2
+    },
+    /// Bid on the auction of a name, the bid is held in the auction pot until it is claimed. Outbid
+    /// bids are credited to the bid refund account of their bidder, see WithdrawRefund.
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                                 |
+    /// |-------|----------|--------|---------------------------------------------|
+    /// | 0     | ❌        | ❌      | The SPL token program                       |
+    /// | 1     | ✅        | ❌      | The auction account                         |
+    /// | 2     | ✅        | ❌      | The auction pot token account               |
+    /// | 3     | ✅        | ✅      | The bidder account                          |
+    /// | 4     | ✅        | ❌      | The bidder token account                    |
+    /// | 5     | ❌        | ❌      | The central state account                   |
+    /// | 6     | ❌        | ❌      | The system program account                  |
+    /// | 7     | ❌        | ❌      | The rent sysvar account                     |
+    /// | 8     | ✅        | ❌      | The bid refund account of the outbid bidder |
+    ///
+    /// The outbid bidder's refund account is only required when the auction already has a bid
+    PlaceBid {
+        amount: u64,
+    },
+    /// Commit to a sealed bid, the deposit is held in the auction pot and must cover the revealed bid
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The system program account    |
+    /// | 1     | ❌        | ❌      | The SPL token program         |
+    /// | 2     | ✅        | ❌      | The auction account           |
+    /// | 3     | ✅        | ❌      | The auction pot token account |
+    /// | 4     | ✅        | ❌      | The sealed bid account        |
+    /// | 5     | ✅        | ✅      | The bidder account            |
+    /// | 6     | ✅        | ❌      | The bidder token account      |
+    /// | 7     | ❌        | ❌      | The rent sysvar account       |
+    /// | 8     | ❌        | ❌      | The central state account     |
+    CommitBid {
+        commitment: [u8; 32], // See `SealedBid::commitment`
+        deposit: u64,
+    },
+    /// Reveal a sealed bid once the bidding period is over
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The auction account           |
+    /// | 1     | ✅        | ❌      | The sealed bid account        |
+    /// | 2     | ❌        | ✅      | The bidder account            |
+    RevealBid {
+        amount: u64,
+        salt: [u8; 32],
+    },
+    /// Credit the deposit of a sealed bid to the bid refund account of the bidder once the auction
+    /// has ended, less the bid of the winner
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The system program account    |
+    /// | 1     | ✅        | ❌      | The auction account           |
+    /// | 2     | ✅        | ❌      | The sealed bid account        |
+    /// | 3     | ✅        | ❌      | The bidder account            |
+    /// | 4     | ✅        | ❌      | The bid refund account        |
+    /// | 5     | ✅        | ✅      | The fee payer account         |
+    /// | 6     | ❌        | ❌      | The rent sysvar account       |
+    RefundSealedBid,
+    /// Reveal the hidden reserve of a reselling auction once it has ended, the auction only settles
+    /// to the top bidder if the reserve is met. Unrevealed reserves count as unmet after the
+    /// configured reveal length.
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The auction account           |
+    /// | 1     | ❌        | ❌      | The reselling state account   |
+    /// | 2     | ❌        | ❌      | The destination token account |
+    /// | 3     | ❌        | ✅      | The seller account            |
+    /// | 4     | ❌        | ❌      | The central state account     |
+    RevealReserve {
+        reserve: u64,
+        salt: [u8; 32],
+    },
+    /// Accept the top bid of a reselling auction that does not meet the hidden reserve, within the
+    /// configured acceptance window after the auction has ended. The auction is then settled with
+    /// Claim.
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The auction account           |
+    /// | 1     | ❌        | ❌      | The reselling state account   |
+    /// | 2     | ❌        | ❌      | The destination token account |
+    /// | 3     | ❌        | ✅      | The seller account            |
+    /// | 4     | ❌        | ❌      | The central state account     |
+    AcceptBid,
+    /// Upgrade the central state to the current version, new configuration fields are set to their
+    /// defaults. The legacy central state only stores its signer nonce.
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ✅        | ❌      | The central state account     |
+    /// | 1     | ❌        | ❌      | The system program account    |
+    /// | 2     | ✅        | ✅      | The fee payer account         |
+    /// | 3     | ❌        | ❌      | The rent sysvar account       |
+    MigrateCentralState,
+    /// Withdraw the refunds credited to a bidder by an auction, see `auction::BidRefund`
+    /// Accounts expected by this instruction
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// |-------|----------|--------|-------------------------------|
+    /// | 0     | ❌        | ❌      | The SPL token program         |
+    /// | 1     | ❌        | ❌      | The auction account           |
+    /// | 2     | ✅        | ❌      | The auction pot token account |
+    /// | 3     | ✅        | ❌      | The bid refund account        |
+    /// | 4     | ✅        | ✅      | The bidder account            |
+    /// | 5     | ✅        | ❌      | The destination token account |
+    /// | 6     | ❌        | ❌      | The central state account     |
+    WithdrawRefund,
+}
+
+impl ProgramInstruction {
+    /// The pause flag checked before processing the instruction. Admin instructions, bid reveals
+    /// and refunds cannot be paused
+    pub fn pause_flag(&self) -> Option<u64> {
+        match self {
+            ProgramInstruction::Create { .. } => Some(PAUSE_CREATE),
+            ProgramInstruction::Claim { .. } => Some(PAUSE_CLAIM),
+            ProgramInstruction::Resell { .. } => Some(PAUSE_RESELL),
+            ProgramInstruction::CreateReverse { .. } => Some(PAUSE_CREATE_REVERSE),
+            ProgramInstruction::EndAuction { .. } => Some(PAUSE_END_AUCTION),
+            ProgramInstruction::CreateV2 { .. } => Some(PAUSE_CREATE_V2),
+            ProgramInstruction::CreateV2Batch { .. } => Some(PAUSE_CREATE_V2),
+            ProgramInstruction::Renew { .. } => Some(PAUSE_RENEW),
+            ProgramInstruction::ReclaimExpired => Some(PAUSE_RECLAIM_EXPIRED),
+            ProgramInstruction::PlaceBid { .. } => Some(PAUSE_PLACE_BID),
+            ProgramInstruction::CommitBid { .. } => Some(PAUSE_PLACE_BID),
+            _ => None,
+        }
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn init(
     program_id: Pubkey,
     state_account: Pubkey,
     fee_payer: Pubkey,
     state_nonce: u8,
+    admin: Pubkey,
+    admin_claim_key: Pubkey,
+    admin_create_key: Pubkey,
+    config: ProgramConfig,
 ) -> Instruction {
-    let data = ProgramInstruction::Init { state_nonce }
-        .try_to_vec()
-        .unwrap();
+    let data = ProgramInstruction::Init {
+        state_nonce,
+        admin: admin.to_bytes(),
+        admin_claim_key: admin_claim_key.to_bytes(),
+        admin_create_key: admin_create_key.to_bytes(),
+        config,
+    }
+    .try_to_vec()
+    .unwrap();
     let accounts = vec![
         AccountMeta::new(state_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
@@ -234,7 +672,6 @@ pub fn init(
 #[allow(clippy::too_many_arguments)]
 pub fn create(
     program_id: Pubkey,
-    auction_program_id: Pubkey,
     root_domain: Pubkey,
     name_account: Pubkey,
     reverse_lookup_account: Pubkey,
@@ -243,9 +680,21 @@ pub fn create(
     state_account: Pubkey,
     fee_payer: Pubkey,
     quote_mint: Pubkey,
+    pyth_fida_price_account: Pubkey,
+    pyth_fida_fallback_price_account: Pubkey,
     name: String,
+    sealed_bid: bool,
+    second_price: bool,
 ) -> Instruction {
-    let data = ProgramInstruction::Create { name }.try_to_vec().unwrap();
+    let blocklist_page = blocklist_page_key(&program_id, &name);
+    let (auction_pot, _) = Auction::find_pot_key(&program_id, &name_account);
+    let data = ProgramInstruction::Create {
+        name,
+        sealed_bid,
+        second_price,
+    }
+    .try_to_vec()
+    .unwrap();
     let accounts = vec![
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
@@ -254,13 +703,17 @@ pub fn create(
         AccountMeta::new_readonly(name_account, false),
         AccountMeta::new(reverse_lookup_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(auction_program_id, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new(auction_account, false),
         AccountMeta::new_readonly(central_state_account, false),
         AccountMeta::new(state_account, false),
         AccountMeta::new(fee_payer, true),
-        AccountMeta::new(quote_mint, false),
-        AccountMeta::new_readonly(PYTH_FIDA_PRICE_ACC, false),
+        AccountMeta::new_readonly(quote_mint, false),
+        AccountMeta::new_readonly(pyth_fida_price_account, false),
+        AccountMeta::new_readonly(blocklist_page, false),
+        AccountMeta::new_readonly(PriceSchedule::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(pyth_fida_fallback_price_account, false),
+        AccountMeta::new(auction_pot, false),
     ];
     Instruction {
         program_id,
@@ -309,49 +762,56 @@ pub fn create_reverse(
 #[allow(clippy::too_many_arguments)]
 pub fn claim(
     program_id: Pubkey,
-    auction_program_id: Pubkey,
     root_domain: Pubkey,
     name_account: Pubkey,
     auction_account: Pubkey,
     state_account: Pubkey,
+    reselling_state_account: Pubkey,
     central_state_account: Pubkey,
     fee_payer: Pubkey,
     destination_token_account: Pubkey,
-    quote_mint: Pubkey,
     bidder_wallet: Pubkey,
-    bidder_pot: Pubkey,
-    bidder_pot_token: Pubkey,
     space: u32,
     hashed_name: [u8; 32],
+    bonfida_vault: Pubkey,
     discount_account: Pubkey,
-    buy_now: Pubkey,
-    bonfida_sol_vault: Pubkey,
+    top_bidder: Pubkey,
+    referrer: Option<Pubkey>,
 ) -> Instruction {
     let data = ProgramInstruction::Claim { hashed_name, space }
         .try_to_vec()
         .unwrap();
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(spl_name_service::id(), false),
         AccountMeta::new_readonly(root_domain, false),
-        AccountMeta::new_readonly(name_account, false),
+        AccountMeta::new(name_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new(auction_account, false),
         AccountMeta::new_readonly(central_state_account, false),
         AccountMeta::new(state_account, false),
-        AccountMeta::new_readonly(auction_program_id, false),
+        AccountMeta::new(reselling_state_account, false),
         AccountMeta::new(fee_payer, true),
-        AccountMeta::new(quote_mint, false),
         AccountMeta::new(destination_token_account, false),
         AccountMeta::new_readonly(bidder_wallet, true),
-        AccountMeta::new(bidder_pot, false),
-        AccountMeta::new(bidder_pot_token, false),
-        AccountMeta::new(BONFIDA_FIDA_VAULT, false),
+        AccountMeta::new(Auction::find_pot_key(&program_id, &name_account).0, false),
+        AccountMeta::new(bonfida_vault, false),
         AccountMeta::new_readonly(discount_account, false),
-        AccountMeta::new(buy_now, false),
-        AccountMeta::new(bonfida_sol_vault, false),
+        AccountMeta::new(Registration::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new(
+            BidRefund::find_key(&program_id, &auction_account, &top_bidder).0,
+            false,
+        ),
     ];
+    if let Some(referrer) = referrer {
+        accounts.push(AccountMeta::new(referrer, false));
+        accounts.push(AccountMeta::new(
+            ReferralStats::find_key(&program_id, &referrer).0,
+            false,
+        ));
+    }
 
     Instruction {
         program_id,
@@ -363,7 +823,6 @@ pub fn claim(
 #[allow(clippy::too_many_arguments)]
 pub fn resell(
     program_id: Pubkey,
-    auction_program_id: Pubkey,
     root_domain: Pubkey,
     name_account: Pubkey,
     name_owner_account: Pubkey,
@@ -374,16 +833,26 @@ pub fn resell(
     fee_payer: Pubkey,
     reselling_state_account: Pubkey,
     destination_token_account: Pubkey,
+    quote_mint: Pubkey,
     name: String,
     minimum_price: u64,
     auction_duration: u64,
     max_price: Option<u64>,
+    end_auction_gap: Option<u64>,
+    bid_increment_bps: Option<u64>,
+    second_price: bool,
+    reserve_commitment: Option<[u8; 32]>,
 ) -> Instruction {
+    let (auction_pot, _) = Auction::find_pot_key(&program_id, &name_account);
     let data = ProgramInstruction::Resell {
         name,
         minimum_price,
         end_auction_at: auction_duration,
         max_price,
+        end_auction_gap,
+        bid_increment_bps,
+        second_price,
+        reserve_commitment,
     }
     .try_to_vec()
     .unwrap();
@@ -396,13 +865,15 @@ pub fn resell(
         AccountMeta::new_readonly(name_owner_account, true),
         AccountMeta::new(reverse_lookup_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(auction_program_id, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new(auction_account, false),
         AccountMeta::new_readonly(central_state_account, false),
         AccountMeta::new(state_account, false),
         AccountMeta::new(reselling_state_account, false),
         AccountMeta::new(destination_token_account, false),
         AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(quote_mint, false),
+        AccountMeta::new(auction_pot, false),
     ];
 
     Instruction {
@@ -411,22 +882,24 @@ pub fn resell(
         data,
     }
 }
+#[allow(clippy::too_many_arguments)]
 pub fn reset_auction(
     program_id: Pubkey,
-    auction_program_id: Pubkey,
-    admin: Pubkey,
     auction: Pubkey,
     name: Pubkey,
     state: Pubkey,
+    central_state: Pubkey,
+    proposal: Pubkey,
 ) -> Instruction {
     let data = ProgramInstruction::ResetAuction.try_to_vec().unwrap();
     let accounts = vec![
-        AccountMeta::new_readonly(auction_program_id, false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
-        AccountMeta::new_readonly(admin, true),
         AccountMeta::new(auction, false),
         AccountMeta::new_readonly(name, false),
         AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
     ];
     Instruction {
         program_id,
@@ -439,28 +912,32 @@ pub fn reset_auction(
 pub fn create_admin(
     program_id: Pubkey,
     fee_payer: Pubkey,
-    admin: Pubkey,
     root_domain: Pubkey,
     name_account: Pubkey,
     reverse_lookup_account: Pubkey,
     central_state_account: Pubkey,
+    proposal: Pubkey,
     params: create_admin::Params,
 ) -> Instruction {
+    let recipient = Pubkey::new(&params.recipient);
+    let (reservation, _) = Reservation::find_key(&program_id, &name_account);
     let data = ProgramInstruction::CreateAdmin(params)
         .try_to_vec()
         .unwrap();
 
     let accounts = vec![
         AccountMeta::new(fee_payer, true),
-        AccountMeta::new(admin, true),
         AccountMeta::new_readonly(spl_name_service::id(), false),
         AccountMeta::new_readonly(root_domain, false),
         AccountMeta::new(name_account, false),
         AccountMeta::new(reverse_lookup_account, false),
         AccountMeta::new_readonly(central_state_account, false),
-        AccountMeta::new_readonly(central_state_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new_readonly(recipient, false),
+        AccountMeta::new(reservation, false),
     ];
 
     Instruction {
@@ -473,43 +950,46 @@ pub fn create_admin(
 #[allow(clippy::too_many_arguments)]
 pub fn admin_claim(
     program_id: Pubkey,
-    auction_program_id: Pubkey,
     name_account: Pubkey,
-    auction_account: Pubkey,
-    state_account: Pubkey,
     central_state_account: Pubkey,
-    bidder_wallet: Pubkey,
-    bidder_pot: Pubkey,
-    bidder_pot_token: Pubkey,
-    admin: Pubkey,
+    bonfida_vault: Pubkey,
     new_name_owner: Pubkey,
     fee_payer: Pubkey,
-    root_name: Pubkey,
+    proposal: Pubkey,
     hashed_name: [u8; 32],
     space: u32,
+    top_bidder: Option<Pubkey>,
 ) -> Instruction {
     let data = ProgramInstruction::ClaimAdmin { hashed_name, space }
         .try_to_vec()
         .unwrap();
-    let accounts = vec![
+    let (state_account, _) = Pubkey::find_program_address(&[&name_account.to_bytes()], &program_id);
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let mut accounts = vec![
         AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(spl_name_service::id(), false),
+        AccountMeta::new_readonly(ROOT_DOMAIN_ACCOUNT, false),
         AccountMeta::new(name_account, false),
         AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(auction_program_id, false),
-        AccountMeta::new(auction_account, false),
+        AccountMeta::new(auction, false),
         AccountMeta::new_readonly(central_state_account, false),
-        AccountMeta::new(state_account, false),
-        AccountMeta::new_readonly(bidder_wallet, false),
-        AccountMeta::new(bidder_pot, false),
-        AccountMeta::new(bidder_pot_token, false),
-        AccountMeta::new(BONFIDA_USDC_VAULT, false),
-        AccountMeta::new_readonly(admin, true),
+        AccountMeta::new_readonly(state_account, false),
+        AccountMeta::new(Auction::find_pot_key(&program_id, &name_account).0, false),
+        AccountMeta::new(bonfida_vault, false),
         AccountMeta::new_readonly(new_name_owner, false),
         AccountMeta::new(fee_payer, true),
-        AccountMeta::new_readonly(root_name, false),
+        AccountMeta::new(Registration::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
     ];
+    if let Some(top_bidder) = top_bidder {
+        accounts.push(AccountMeta::new(
+            BidRefund::find_key(&program_id, &auction, &top_bidder).0,
+            false,
+        ));
+    }
 
     Instruction {
         program_id,
@@ -526,30 +1006,44 @@ pub fn end_auction(
     auction: Pubkey,
     central_state: Pubkey,
     state: Pubkey,
-    auction_program_id: Pubkey,
     auction_creator: Pubkey,
     reselling_state: Pubkey,
     destination_token: Pubkey,
+    bonfida_sol_vault: Pubkey,
     name: String,
+    top_bidder: Option<Pubkey>,
+    bonfida_fida_vault: Pubkey,
 ) -> Instruction {
     let data = ProgramInstruction::EndAuction { name }
         .try_to_vec()
         .unwrap();
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(spl_name_service::id(), false),
         AccountMeta::new_readonly(root_domain, false),
         AccountMeta::new(name_account, false),
-        AccountMeta::new_readonly(auction_program_id, false),
         AccountMeta::new(auction, false),
         AccountMeta::new_readonly(central_state, false),
         AccountMeta::new(state, false),
         AccountMeta::new(auction_creator, true),
         AccountMeta::new(reselling_state, false),
         AccountMeta::new(destination_token, false),
-        AccountMeta::new(BONFIDA_SOL_VAULT, false),
+        AccountMeta::new(bonfida_sol_vault, false),
         AccountMeta::new(system_program::id(), false),
     ];
+    if let Some(top_bidder) = top_bidder {
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+        accounts.push(AccountMeta::new(
+            Auction::find_pot_key(&program_id, &name_account).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            BidRefund::find_key(&program_id, &auction, &top_bidder).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new(bonfida_fida_vault, false));
+        accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    }
 
     Instruction {
         program_id,
@@ -567,14 +1061,30 @@ pub fn create_v2(
     central_state: Pubkey,
     buyer: Pubkey,
     buyer_token_source: Pubkey,
+    vault: Pubkey,
     state: Pubkey,
+    quote_mint: Pubkey,
+    pyth_price_acc: Pubkey,
     name: String,
     space: u32,
+    premium_proofs: Vec<PremiumProof>,
+    voucher: Option<Voucher>,
+    referrer: Option<Pubkey>,
 ) -> Instruction {
-    let data = ProgramInstruction::CreateV2 { name, space }
-        .try_to_vec()
-        .unwrap();
-    let accounts = vec![
+    let blocklist_page = blocklist_page_key(&program_id, &name);
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let used_voucher = voucher
+        .as_ref()
+        .map(|v| UsedVoucher::find_key(&program_id, v.nonce).0);
+    let data = ProgramInstruction::CreateV2 {
+        name,
+        space,
+        premium_proofs,
+        voucher,
+    }
+    .try_to_vec()
+    .unwrap();
+    let mut accounts = vec![
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(spl_name_service::id(), false),
         AccountMeta::new_readonly(root_domain, false),
@@ -584,10 +1094,28 @@ pub fn create_v2(
         AccountMeta::new_readonly(central_state, false),
         AccountMeta::new(buyer, true),
         AccountMeta::new(buyer_token_source, false),
-        AccountMeta::new(BONFIDA_USDC_VAULT, false),
+        AccountMeta::new(vault, false),
         AccountMeta::new_readonly(spl_token::ID, false),
         AccountMeta::new_readonly(state, false),
+        AccountMeta::new_readonly(blocklist_page, false),
+        AccountMeta::new_readonly(PriceSchedule::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(auction, false),
+        AccountMeta::new_readonly(quote_mint, false),
+        AccountMeta::new_readonly(PaymentMint::find_key(&program_id, &quote_mint).0, false),
+        AccountMeta::new_readonly(pyth_price_acc, false),
+        AccountMeta::new(Registration::find_key(&program_id, &name_account).0, false),
     ];
+    if let Some(used_voucher) = used_voucher {
+        accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+        accounts.push(AccountMeta::new(used_voucher, false));
+    }
+    if let Some(referrer) = referrer {
+        accounts.push(AccountMeta::new(referrer, false));
+        accounts.push(AccountMeta::new(
+            ReferralStats::find_key(&program_id, &referrer).0,
+            false,
+        ));
+    }
 
     Instruction {
         program_id,
@@ -596,22 +1124,670 @@ pub fn create_v2(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn take_back(
     program_id: Pubkey,
-    admin: Pubkey,
     name_account: Pubkey,
+    central_state: Pubkey,
     new_owner: Pubkey,
+    proposal: Pubkey,
+    fee_payer: Pubkey,
+    reason: TakeBackReason,
 ) -> Instruction {
-    let data = ProgramInstruction::TakeBack.try_to_vec().unwrap();
+    let data = ProgramInstruction::TakeBack { reason }
+        .try_to_vec()
+        .unwrap();
+    let (take_back_record, _) = TakeBackRecord::find_key(&program_id, &name_account);
 
     let accounts = vec![
-        AccountMeta::new(admin, true),
         AccountMeta::new_readonly(spl_name_service::id(), false),
         AccountMeta::new(name_account, false),
-        AccountMeta::new_readonly(CENTRAL_STATE, false),
+        AccountMeta::new_readonly(central_state, false),
         AccountMeta::new_readonly(Pubkey::default(), false),
         AccountMeta::new_readonly(ROOT_DOMAIN_ACCOUNT, false),
         AccountMeta::new_readonly(new_owner, false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+        AccountMeta::new(take_back_record, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn update_config(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    proposal: Pubkey,
+    config: ProgramConfig,
+) -> Instruction {
+    let data = ProgramInstruction::UpdateConfig { config }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new(central_state, false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_admin_set(
+    program_id: Pubkey,
+    admin: Pubkey,
+    central_state: Pubkey,
+    fee_payer: Pubkey,
+    proposal: Option<Pubkey>,
+    admins: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    let data = ProgramInstruction::SetAdminSet {
+        admins: admins.iter().map(|k| k.to_bytes()).collect(),
+        threshold,
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(admin, true),
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if let Some(k) = proposal {
+        accounts.push(AccountMeta::new(k, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn create_proposal(
+    program_id: Pubkey,
+    proposer: Pubkey,
+    fee_payer: Pubkey,
+    proposal_index: u64,
+    action: AdminAction,
+) -> Instruction {
+    let data = ProgramInstruction::CreateProposal { action }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(proposer, true),
+        AccountMeta::new(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(Proposal::find_key(&program_id, proposal_index).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn approve_proposal(program_id: Pubkey, approver: Pubkey, proposal: Pubkey) -> Instruction {
+    let data = ProgramInstruction::ApproveProposal.try_to_vec().unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(approver, true),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn propose_authority(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    proposal: Pubkey,
+    role: AuthorityRole,
+    new_key: Pubkey,
+) -> Instruction {
+    let data = ProgramInstruction::ProposeAuthority {
+        role,
+        new_key: new_key.to_bytes(),
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new(central_state, false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn accept_authority(
+    program_id: Pubkey,
+    new_authority: Pubkey,
+    central_state: Pubkey,
+    role: AuthorityRole,
+) -> Instruction {
+    let data = ProgramInstruction::AcceptAuthority { role }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(new_authority, true),
+        AccountMeta::new(central_state, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_pause(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    proposal: Pubkey,
+    flags: u64,
+    paused: bool,
+) -> Instruction {
+    let instruction = if paused {
+        ProgramInstruction::Pause { flags }
+    } else {
+        ProgramInstruction::Unpause { flags }
+    };
+    let data = instruction.try_to_vec().unwrap();
+
+    let accounts = vec![
+        AccountMeta::new(central_state, false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+fn blocklist_page_key(program_id: &Pubkey, name: &str) -> Pubkey {
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()]);
+    BlocklistPage::find_key(program_id, BlocklistPage::page_of(hashed_name.as_ref())).0
+}
+
+pub fn update_blocklist(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    fee_payer: Pubkey,
+    proposal: Pubkey,
+    page: u8,
+    add: Vec<[u8; 32]>,
+    remove: Vec<[u8; 32]>,
+) -> Instruction {
+    let data = ProgramInstruction::UpdateBlocklist { page, add, remove }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(BlocklistPage::find_key(&program_id, page).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_price_schedule(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    fee_payer: Pubkey,
+    proposal: Pubkey,
+    schedule: PriceSchedule,
+) -> Instruction {
+    let data = ProgramInstruction::SetPriceSchedule { schedule }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(PriceSchedule::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn set_payment_mint(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    fee_payer: Pubkey,
+    proposal: Pubkey,
+    payment_mint: PaymentMint,
+) -> Instruction {
+    let payment_mint_key = PaymentMint::find_key(&program_id, &Pubkey::new(&payment_mint.mint)).0;
+    let data = ProgramInstruction::SetPaymentMint { payment_mint }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(payment_mint_key, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(AdminSet::find_key(&program_id).0, false),
+        AccountMeta::new(proposal, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn renew(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    central_state: Pubkey,
+    buyer: Pubkey,
+    buyer_token_source: Pubkey,
+    vault: Pubkey,
+    quote_mint: Pubkey,
+    pyth_price_acc: Pubkey,
+    name: String,
+    years: u8,
+) -> Instruction {
+    let data = ProgramInstruction::Renew { name, years }
+        .try_to_vec()
+        .unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(name_account, false),
+        AccountMeta::new(Registration::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(buyer, true),
+        AccountMeta::new(buyer_token_source, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(PriceSchedule::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(quote_mint, false),
+        AccountMeta::new_readonly(PaymentMint::find_key(&program_id, &quote_mint).0, false),
+        AccountMeta::new_readonly(pyth_price_acc, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn reclaim_expired(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    central_state: Pubkey,
+) -> Instruction {
+    let data = ProgramInstruction::ReclaimExpired.try_to_vec().unwrap();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(spl_name_service::id(), false),
+        AccountMeta::new_readonly(ROOT_DOMAIN_ACCOUNT, false),
+        AccountMeta::new(name_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(Registration::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(central_state, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_v2_batch(
+    program_id: Pubkey,
+    root_domain: Pubkey,
+    central_state: Pubkey,
+    buyer: Pubkey,
+    buyer_token_source: Pubkey,
+    vault: Pubkey,
+    quote_mint: Pubkey,
+    pyth_price_acc: Pubkey,
+    names: Vec<(String, u32)>,
+    premium_proofs: Vec<Vec<PremiumProof>>,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_name_service::id(), false),
+        AccountMeta::new_readonly(root_domain, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new(buyer, true),
+        AccountMeta::new(buyer_token_source, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new_readonly(PriceSchedule::find_key(&program_id).0, false),
+        AccountMeta::new_readonly(quote_mint, false),
+        AccountMeta::new_readonly(PaymentMint::find_key(&program_id, &quote_mint).0, false),
+        AccountMeta::new_readonly(pyth_price_acc, false),
+    ];
+    for (name, _) in &names {
+        let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()])
+            .as_ref()
+            .to_vec();
+        let (name_account, _) = get_seeds_and_key(
+            &spl_name_service::id(),
+            hashed_name,
+            None,
+            Some(&root_domain),
+        );
+        let hashed_reverse_lookup =
+            hashv(&[(HASH_PREFIX.to_owned() + &name_account.to_string()).as_bytes()])
+                .as_ref()
+                .to_vec();
+        let (reverse_lookup, _) = get_seeds_and_key(
+            &spl_name_service::id(),
+            hashed_reverse_lookup,
+            Some(&central_state),
+            None,
+        );
+        let (state, _) = Pubkey::find_program_address(&[&name_account.to_bytes()], &program_id);
+        accounts.push(AccountMeta::new(name_account, false));
+        accounts.push(AccountMeta::new(reverse_lookup, false));
+        accounts.push(AccountMeta::new_readonly(state, false));
+        accounts.push(AccountMeta::new_readonly(
+            blocklist_page_key(&program_id, name),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            Registration::find_key(&program_id, &name_account).0,
+            false,
+        ));
+    }
+    if let Some(referrer) = referrer {
+        accounts.push(AccountMeta::new(referrer, false));
+        accounts.push(AccountMeta::new(
+            ReferralStats::find_key(&program_id, &referrer).0,
+            false,
+        ));
+    }
+    let data = ProgramInstruction::CreateV2Batch {
+        names,
+        premium_proofs,
+    }
+    .try_to_vec()
+    .unwrap();
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_bid(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    bidder: Pubkey,
+    bidder_token_source: Pubkey,
+    central_state: Pubkey,
+    outbid_bidder: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let data = ProgramInstruction::PlaceBid { amount }
+        .try_to_vec()
+        .unwrap();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(auction, false),
+        AccountMeta::new(Auction::find_pot_key(&program_id, &name_account).0, false),
+        AccountMeta::new(bidder, true),
+        AccountMeta::new(bidder_token_source, false),
+        AccountMeta::new_readonly(central_state, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if let Some(outbid_bidder) = outbid_bidder {
+        accounts.push(AccountMeta::new(
+            BidRefund::find_key(&program_id, &auction, &outbid_bidder).0,
+            false,
+        ));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn commit_bid(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    bidder: Pubkey,
+    bidder_token_source: Pubkey,
+    central_state: Pubkey,
+    commitment: [u8; 32],
+    deposit: u64,
+) -> Instruction {
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let data = ProgramInstruction::CommitBid {
+        commitment,
+        deposit,
+    }
+    .try_to_vec()
+    .unwrap();
+    let accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(auction, false),
+        AccountMeta::new(Auction::find_pot_key(&program_id, &name_account).0, false),
+        AccountMeta::new(SealedBid::find_key(&program_id, &auction, &bidder).0, false),
+        AccountMeta::new(bidder, true),
+        AccountMeta::new(bidder_token_source, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(central_state, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn reveal_bid(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    bidder: Pubkey,
+    amount: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let data = ProgramInstruction::RevealBid { amount, salt }
+        .try_to_vec()
+        .unwrap();
+    let accounts = vec![
+        AccountMeta::new(auction, false),
+        AccountMeta::new(SealedBid::find_key(&program_id, &auction, &bidder).0, false),
+        AccountMeta::new_readonly(bidder, true),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn refund_sealed_bid(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    bidder: Pubkey,
+    fee_payer: Pubkey,
+) -> Instruction {
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let data = ProgramInstruction::RefundSealedBid.try_to_vec().unwrap();
+    let accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(auction, false),
+        AccountMeta::new(SealedBid::find_key(&program_id, &auction, &bidder).0, false),
+        AccountMeta::new(bidder, false),
+        AccountMeta::new(BidRefund::find_key(&program_id, &auction, &bidder).0, false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_reserve(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    reselling_state: Pubkey,
+    destination_token: Pubkey,
+    seller: Pubkey,
+    central_state: Pubkey,
+    reserve: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    let data = ProgramInstruction::RevealReserve { reserve, salt }
+        .try_to_vec()
+        .unwrap();
+    let accounts = vec![
+        AccountMeta::new(Auction::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(reselling_state, false),
+        AccountMeta::new_readonly(destination_token, false),
+        AccountMeta::new_readonly(seller, true),
+        AccountMeta::new_readonly(central_state, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn accept_bid(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    reselling_state: Pubkey,
+    destination_token: Pubkey,
+    seller: Pubkey,
+    central_state: Pubkey,
+) -> Instruction {
+    let data = ProgramInstruction::AcceptBid.try_to_vec().unwrap();
+    let accounts = vec![
+        AccountMeta::new(Auction::find_key(&program_id, &name_account).0, false),
+        AccountMeta::new_readonly(reselling_state, false),
+        AccountMeta::new_readonly(destination_token, false),
+        AccountMeta::new_readonly(seller, true),
+        AccountMeta::new_readonly(central_state, false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn migrate_central_state(
+    program_id: Pubkey,
+    central_state: Pubkey,
+    fee_payer: Pubkey,
+) -> Instruction {
+    let data = ProgramInstruction::MigrateCentralState
+        .try_to_vec()
+        .unwrap();
+    let accounts = vec![
+        AccountMeta::new(central_state, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(fee_payer, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+pub fn withdraw_refund(
+    program_id: Pubkey,
+    name_account: Pubkey,
+    bidder: Pubkey,
+    destination_token: Pubkey,
+    central_state: Pubkey,
+) -> Instruction {
+    let (auction, _) = Auction::find_key(&program_id, &name_account);
+    let data = ProgramInstruction::WithdrawRefund.try_to_vec().unwrap();
+    let accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(auction, false),
+        AccountMeta::new(Auction::find_pot_key(&program_id, &name_account).0, false),
+        AccountMeta::new(BidRefund::find_key(&program_id, &auction, &bidder).0, false),
+        AccountMeta::new(bidder, true),
+        AccountMeta::new(destination_token, false),
+        AccountMeta::new_readonly(central_state, false),
     ];
 
     Instruction {
@@ -1,6 +1,9 @@
+pub mod auction;
 pub mod entrypoint;
 pub mod error;
 pub mod instructions;
+mod migration;
+pub mod oracle;
 pub mod processor;
 pub mod state;
 mod utils;
@@ -0,0 +1,157 @@
+use solana_program::{msg, program_error::ProgramError, pubkey, pubkey::Pubkey};
+
+use crate::state::CENTRAL_STATE_VERSION;
+
+// The central state deployed before it was versioned only stores its signer nonce, the
+// configuration was hardcoded in the program
+pub const LEGACY_VERSION: u8 = 0;
+
+// Nonce, version and the three authority keys precede the program configuration
+const HEADER_LEN: usize = 2 + 3 * 32;
+
+// Defaults of the fields added to the central state after the first version
+const AUTHORITY_CHANGE_DELAY: u64 = 172_800; // 2 days in seconds
+const ORACLE_MAX_AGE: u64 = 25; // In slots
+const ORACLE_MAX_CONFIDENCE_BPS: u64 = 200;
+const BID_INCREMENT_BPS: u64 = 100;
+const REVEAL_LENGTH: u64 = 86_400; // 1 day in seconds
+
+mod legacy {
+    use super::*;
+
+    pub const ADMIN: Pubkey = pubkey!("BD4vT1aztHmuEPZh7GgvpeFskgyhi9AtPwtxzYEh5J91");
+    pub const ADMIN_CLAIM_KEY: Pubkey = pubkey!("VBx642K1hYGLU5Zm1CHW1uRXAtFgxN5mRqyMcXnLZFW");
+    pub const ADMIN_CREATE_KEY: Pubkey = pubkey!("CHG6XM8Ugk7xkZMcp5PMoJTYfwCG7XStv5rt2w1eQKPS");
+    pub const BONFIDA_FIDA_VAULT: Pubkey = pubkey!("AUoZ3YAhV3b2rZeEH93UMZHXUZcTramBvb4d9YEVySkc");
+    pub const BONFIDA_USDC_VAULT: Pubkey = pubkey!("DmSyHDSM9eSLyvoLsPvDr5fRRFZ7Bfr3h3ULvWpgQaq7");
+    pub const BONFIDA_SOL_VAULT: Pubkey = pubkey!("GcWEQ9K78FV7LEHteFVciYApERk5YvQuFDQPk1yYJVXi");
+    pub const PYTH_FIDA_PRICE_ACC: Pubkey = pubkey!("ETp9eKXVv1dWwHSpsXRUuXHmw24PwRkttCGVgpZEY9zF");
+    pub const MINIMUM_PRICE_USD: u64 = 20_000_000; // 20 USD
+    pub const AUCTION_MAX_LENGTH: u64 = 259200; // 3 days in seconds
+    pub const END_AUCTION_GAP: u64 = 600;
+    pub const FEES: [u64; 5] = [500, 300, 200, 150, 100];
+    pub const FEE_TIERS: [u64; 4] = [10_000_000, 100_000_000, 500_000_000, 1_000_000_000];
+}
+
+/// Upgrades raw central state data to the current version, one version at a time
+pub fn migrate(mut data: Vec<u8>) -> Result<Vec<u8>, ProgramError> {
+    let mut version = match data.len() {
+        0 => return Err(ProgramError::UninitializedAccount),
+        1 => LEGACY_VERSION,
+        _ => data[1],
+    };
+    if version >= CENTRAL_STATE_VERSION {
+        msg!("The central state is already up to date");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    while version < CENTRAL_STATE_VERSION {
+        data = upgrade(version, data);
+        version += 1;
+        data[1] = version;
+    }
+    Ok(data)
+}
+
+// Converts the data of a given version to the layout of the next version
+fn upgrade(version: u8, mut data: Vec<u8>) -> Vec<u8> {
+    match version {
+        LEGACY_VERSION => {
+            let mut new_data = vec![data[0], 1];
+            new_data.extend_from_slice(&legacy::ADMIN.to_bytes());
+            new_data.extend_from_slice(&legacy::ADMIN_CLAIM_KEY.to_bytes());
+            new_data.extend_from_slice(&legacy::ADMIN_CREATE_KEY.to_bytes());
+            new_data.extend_from_slice(&legacy::BONFIDA_FIDA_VAULT.to_bytes());
+            new_data.extend_from_slice(&legacy::BONFIDA_USDC_VAULT.to_bytes());
+            new_data.extend_from_slice(&legacy::BONFIDA_SOL_VAULT.to_bytes());
+            new_data.extend_from_slice(&legacy::PYTH_FIDA_PRICE_ACC.to_bytes());
+            new_data.extend_from_slice(&legacy::MINIMUM_PRICE_USD.to_le_bytes());
+            new_data.extend_from_slice(&legacy::AUCTION_MAX_LENGTH.to_le_bytes());
+            new_data.extend_from_slice(&legacy::END_AUCTION_GAP.to_le_bytes());
+            for v in legacy::FEES.iter().chain(legacy::FEE_TIERS.iter()) {
+                new_data.extend_from_slice(&v.to_le_bytes());
+            }
+            new_data
+        }
+        // Adds the authority change delay at the end of the configuration, and an empty list of
+        // pending authorities
+        1 => {
+            data = insert(
+                data,
+                HEADER_LEN + 224,
+                &AUTHORITY_CHANGE_DELAY.to_le_bytes(),
+            );
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data
+        }
+        // Adds the pause bitmask after the configuration, nothing is paused
+        2 => insert(data, HEADER_LEN + 232, &0u64.to_le_bytes()),
+        // Drops the minimum USD price, prices are read from the price schedule account
+        3 => {
+            data.drain(HEADER_LEN + 128..HEADER_LEN + 136);
+            data
+        }
+        // Uses the main price feed as fallback until one is configured, and adds the oracle
+        // guards at the end of the configuration
+        4 => {
+            let pyth_fida_price_acc = data[HEADER_LEN + 96..HEADER_LEN + 128].to_vec();
+            data = insert(data, HEADER_LEN + 128, &pyth_fida_price_acc);
+            let mut guards = ORACLE_MAX_AGE.to_le_bytes().to_vec();
+            guards.extend_from_slice(&ORACLE_MAX_CONFIDENCE_BPS.to_le_bytes());
+            insert(data, HEADER_LEN + 256, &guards)
+        }
+        // Referrers are not paid until the admins set their share
+        5 => insert(data, HEADER_LEN + 272, &0u64.to_le_bytes()),
+        // Adds the bid increment and the auction parameter bounds, sellers keep the configured end
+        // gap and increment until the admins widen the bounds
+        6 => {
+            let end_auction_gap = data[HEADER_LEN + 168..HEADER_LEN + 176].to_vec();
+            let mut params = BID_INCREMENT_BPS.to_le_bytes().to_vec();
+            params.extend_from_slice(&end_auction_gap);
+            params.extend_from_slice(&end_auction_gap);
+            params.extend_from_slice(&BID_INCREMENT_BPS.to_le_bytes());
+            params.extend_from_slice(&BID_INCREMENT_BPS.to_le_bytes());
+            insert(data, HEADER_LEN + 280, &params)
+        }
+        // Adds the reveal length of sealed bid auctions
+        7 => insert(data, HEADER_LEN + 320, &REVEAL_LENGTH.to_le_bytes()),
+        // Sellers keep the reveal length to accept a bid below their reserve
+        8 => {
+            let reveal_length = data[HEADER_LEN + 320..HEADER_LEN + 328].to_vec();
+            insert(data, HEADER_LEN + 328, &reveal_length)
+        }
+        // Sellers can cancel auctions with bids without penalty until the admins set one
+        9 => insert(data, HEADER_LEN + 336, &[0; 16]),
+        _ => unreachable!(),
+    }
+}
+
+fn insert(mut data: Vec<u8>, offset: usize, bytes: &[u8]) -> Vec<u8> {
+    data.splice(offset..offset, bytes.iter().cloned());
+    data
+}
+
+#[test]
+pub fn test_migrate_legacy() {
+    let data = migrate(vec![254]).unwrap();
+    assert_eq!(
+        data.len(),
+        HEADER_LEN + 5 * 32 + 2 * 8 + 5 * 8 + 4 * 8 + 13 * 8 + 8 + 4
+    );
+    assert_eq!(data[0], 254);
+    assert_eq!(data[1], CENTRAL_STATE_VERSION);
+    assert_eq!(&data[2..34], &legacy::ADMIN.to_bytes());
+    assert_eq!(
+        &data[HEADER_LEN + 248..HEADER_LEN + 256],
+        &AUTHORITY_CHANGE_DELAY.to_le_bytes()
+    );
+    assert_eq!(
+        &data[HEADER_LEN + 128..HEADER_LEN + 160],
+        &legacy::PYTH_FIDA_PRICE_ACC.to_bytes()
+    );
+    assert_eq!(
+        &data[HEADER_LEN + 288..HEADER_LEN + 296],
+        &legacy::END_AUCTION_GAP.to_le_bytes()
+    );
+    assert!(migrate(data).is_err());
+    assert!(migrate(vec![]).is_err());
+}
@@ -0,0 +1,304 @@
+use std::convert::TryFrom;
+
+#[cfg(feature = "test-bpf")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "test-bpf")]
+use solana_program::program_pack::{Pack, Sealed};
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{error::NameAuctionError, state::ProgramConfig};
+
+// Byte offsets in a Pyth v2 price account
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_AGG_PUB_SLOT_OFFSET: usize = 232;
+const PYTH_PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+/// A price as published by an oracle, worth `price * 10^expo` quote tokens per base token
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub trading: bool,
+    pub pub_slot: u64,
+}
+
+impl OraclePrice {
+    /// Price of the smallest unit of the base token in the smallest unit of the quote token
+    pub fn get_price_fp32(
+        &self,
+        base_decimals: u8,
+        quote_decimals: u8,
+        current_slot: u64,
+        config: &ProgramConfig,
+    ) -> Result<u64, ProgramError> {
+        if !self.trading || current_slot.saturating_sub(self.pub_slot) > config.oracle_max_age {
+            msg!("The oracle price was published at slot {}", self.pub_slot);
+            return Err(NameAuctionError::StaleOracle.into());
+        }
+        if self.price <= 0 {
+            msg!("Invalid oracle price");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if (self.conf as u128) * 10_000
+            > (self.price as u128) * (config.oracle_max_confidence_bps as u128)
+        {
+            msg!(
+                "The oracle confidence is {} for a price of {}",
+                self.conf,
+                self.price
+            );
+            return Err(NameAuctionError::OracleConfidenceTooWide.into());
+        }
+
+        let mut numerator =
+            ((self.price as u128) << 32).checked_mul(10u128.pow(quote_decimals as u32));
+        let mut denominator = 10u128.checked_pow(base_decimals as u32);
+        if self.expo < 0 {
+            denominator = denominator
+                .and_then(|d| d.checked_mul(10u128.checked_pow(self.expo.unsigned_abs())?));
+        } else {
+            numerator =
+                numerator.and_then(|n| n.checked_mul(10u128.checked_pow(self.expo as u32)?));
+        }
+        numerator
+            .zip(denominator)
+            .and_then(|(n, d)| u64::try_from(n / d).ok())
+            .filter(|&p| p != 0)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+}
+
+pub trait PriceSource {
+    fn get_oracle_price(&self) -> Result<OraclePrice, ProgramError>;
+}
+
+pub struct PythPriceSource<'a, 'b: 'a> {
+    pub account: &'a AccountInfo<'b>,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    let mut res = [0; N];
+    res.copy_from_slice(&data[offset..offset + N]);
+    res
+}
+
+impl<'a, 'b: 'a> PriceSource for PythPriceSource<'a, 'b> {
+    fn get_oracle_price(&self) -> Result<OraclePrice, ProgramError> {
+        let data = self.account.data.borrow();
+        if data.len() < PYTH_PRICE_ACCOUNT_MIN_LEN
+            || u32::from_le_bytes(read_bytes(&data, 0)) != PYTH_MAGIC
+            || u32::from_le_bytes(read_bytes(&data, 8)) != PYTH_PRICE_ACCOUNT_TYPE
+        {
+            msg!("Invalid Pyth price account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(OraclePrice {
+            price: i64::from_le_bytes(read_bytes(&data, PYTH_AGG_PRICE_OFFSET)),
+            conf: u64::from_le_bytes(read_bytes(&data, PYTH_AGG_CONF_OFFSET)),
+            expo: i32::from_le_bytes(read_bytes(&data, PYTH_EXPO_OFFSET)),
+            trading: u32::from_le_bytes(read_bytes(&data, PYTH_AGG_STATUS_OFFSET))
+                == PYTH_STATUS_TRADING,
+            pub_slot: u64::from_le_bytes(read_bytes(&data, PYTH_AGG_PUB_SLOT_OFFSET)),
+        })
+    }
+}
+
+/// Price account written directly by tests, it can only be owned by the program when injected in a test validator
+#[cfg(feature = "test-bpf")]
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct TestPrice {
+    pub tag: u64,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub trading: bool,
+    pub pub_slot: u64,
+}
+
+#[cfg(feature = "test-bpf")]
+impl TestPrice {
+    pub const TAG: u64 = u64::from_le_bytes(*b"testprce");
+
+    pub fn new(price: i64, conf: u64, expo: i32, pub_slot: u64) -> Self {
+        Self {
+            tag: Self::TAG,
+            price,
+            conf,
+            expo,
+            trading: true,
+            pub_slot,
+        }
+    }
+}
+
+#[cfg(feature = "test-bpf")]
+impl Sealed for TestPrice {}
+
+#[cfg(feature = "test-bpf")]
+impl Pack for TestPrice {
+    const LEN: usize = 8 + 8 + 8 + 4 + 1 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "test-bpf")]
+pub struct TestPriceSource<'a, 'b: 'a> {
+    pub account: &'a AccountInfo<'b>,
+}
+
+#[cfg(feature = "test-bpf")]
+impl<'a, 'b: 'a> PriceSource for TestPriceSource<'a, 'b> {
+    fn get_oracle_price(&self) -> Result<OraclePrice, ProgramError> {
+        let test_price = TestPrice::unpack_from_slice(&self.account.data.borrow())?;
+        if test_price.tag != TestPrice::TAG {
+            msg!("Invalid test price account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(OraclePrice {
+            price: test_price.price,
+            conf: test_price.conf,
+            expo: test_price.expo,
+            trading: test_price.trading,
+            pub_slot: test_price.pub_slot,
+        })
+    }
+}
+
+// The program never creates accounts holding a test price, so price accounts it owns can only come from tests
+#[cfg(feature = "test-bpf")]
+pub fn load_price_source<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    account: &'a AccountInfo<'b>,
+) -> Box<dyn PriceSource + 'a> {
+    if account.owner == program_id {
+        Box::new(TestPriceSource { account })
+    } else {
+        Box::new(PythPriceSource { account })
+    }
+}
+
+// Deployed builds only read Pyth accounts
+#[cfg(not(feature = "test-bpf"))]
+pub fn load_price_source<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    account: &'a AccountInfo<'b>,
+) -> Box<dyn PriceSource + 'a> {
+    Box::new(PythPriceSource { account })
+}
+
+// Reads the price from the fallback feed when the main one is stale or uncertain
+pub fn get_oracle_price_fp32(
+    program_id: &Pubkey,
+    price_account: &AccountInfo,
+    fallback_price_account: Option<&AccountInfo>,
+    base_decimals: u8,
+    quote_decimals: u8,
+    config: &ProgramConfig,
+) -> Result<u64, ProgramError> {
+    let current_slot = Clock::get()?.slot;
+    let res = load_price_source(program_id, price_account)
+        .get_oracle_price()
+        .and_then(|p| p.get_price_fp32(base_decimals, quote_decimals, current_slot, config));
+    match (res, fallback_price_account) {
+        (Err(e), Some(fallback)) => {
+            msg!("Using the fallback oracle");
+            load_price_source(program_id, fallback)
+                .get_oracle_price()
+                .and_then(|p| p.get_price_fp32(base_decimals, quote_decimals, current_slot, config))
+                .map_err(|_| e)
+        }
+        (res, _) => res,
+    }
+}
+
+#[test]
+pub fn test_pyth_price() {
+    let config = ProgramConfig {
+        bonfida_fida_vault: [0; 32],
+        bonfida_usdc_vault: [0; 32],
+        bonfida_sol_vault: [0; 32],
+        pyth_fida_price_acc: [0; 32],
+        pyth_fida_fallback_price_acc: [0; 32],
+        auction_max_length: 1,
+        end_auction_gap: 1,
+        fees: [0; 5],
+        fee_tiers: [1, 2, 3, 4],
+        authority_change_delay: 1,
+        oracle_max_age: 25,
+        oracle_max_confidence_bps: 200,
+        referral_share_bps: 0,
+        bid_increment_bps: 100,
+        min_end_auction_gap: 1,
+        max_end_auction_gap: 1,
+        min_bid_increment_bps: 100,
+        max_bid_increment_bps: 100,
+        reveal_length: 1,
+        accept_bid_window: 1,
+        cancellation_penalty_bps: 0,
+        cancellation_compensation_bps: 0,
+    };
+    let mut data = vec![0u8; PYTH_PRICE_ACCOUNT_MIN_LEN];
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+    data[PYTH_EXPO_OFFSET..PYTH_EXPO_OFFSET + 4].copy_from_slice(&(-8i32).to_le_bytes());
+    data[PYTH_AGG_PRICE_OFFSET..PYTH_AGG_PRICE_OFFSET + 8]
+        .copy_from_slice(&50_000_000i64.to_le_bytes()); // 0.5 USD
+    data[PYTH_AGG_CONF_OFFSET..PYTH_AGG_CONF_OFFSET + 8].copy_from_slice(&500_000u64.to_le_bytes());
+    data[PYTH_AGG_STATUS_OFFSET..PYTH_AGG_STATUS_OFFSET + 4]
+        .copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+    data[PYTH_AGG_PUB_SLOT_OFFSET..PYTH_AGG_PUB_SLOT_OFFSET + 8]
+        .copy_from_slice(&100u64.to_le_bytes());
+
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let mut lamports = 0;
+    let account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+    let price = PythPriceSource { account: &account }
+        .get_oracle_price()
+        .unwrap();
+
+    assert_eq!(price.get_price_fp32(6, 6, 110, &config).unwrap(), 1 << 31);
+    assert_eq!(
+        price.get_price_fp32(6, 6, 126, &config).unwrap_err(),
+        NameAuctionError::StaleOracle.into()
+    );
+
+    let uncertain_price = OraclePrice {
+        conf: 2_000_000,
+        ..price
+    };
+    assert_eq!(
+        uncertain_price
+            .get_price_fp32(6, 6, 110, &config)
+            .unwrap_err(),
+        NameAuctionError::OracleConfidenceTooWide.into()
+    );
+}
@@ -1,11 +1,23 @@
 use crate::{
     instructions::ProgramInstruction,
     processor::{
-        admin_claim::process_a_claim, claim::process_claim, create::process_create,
-        create_admin::process_create_admin, create_reverse::process_create_reverse,
-        create_v2::process_create_v2, end_auction::process_end_auction, init::process_init,
-        resell::process_resell, reset_auction::process_reset_auction, take_back::process_take_back,
+        accept_authority::process_accept_authority, accept_bid::process_accept_bid,
+        admin_claim::process_a_claim, approve_proposal::process_approve_proposal,
+        claim::process_claim, commit_bid::process_commit_bid, create::process_create,
+        create_admin::process_create_admin, create_proposal::process_create_proposal,
+        create_reverse::process_create_reverse, create_v2::process_create_v2,
+        create_v2_batch::process_create_v2_batch, end_auction::process_end_auction,
+        init::process_init, migrate_central_state::process_migrate_central_state,
+        place_bid::process_place_bid, propose_authority::process_propose_authority,
+        reclaim_expired::process_reclaim_expired, refund_sealed_bid::process_refund_sealed_bid,
+        renew::process_renew, resell::process_resell, reset_auction::process_reset_auction,
+        reveal_bid::process_reveal_bid, reveal_reserve::process_reveal_reserve,
+        set_admin_set::process_set_admin_set, set_pause::process_set_pause,
+        set_payment_mint::process_set_payment_mint, set_price_schedule::process_set_price_schedule,
+        take_back::process_take_back, update_blocklist::process_update_blocklist,
+        update_config::process_update_config, withdraw_refund::process_withdraw_refund,
     },
+    utils::check_not_paused,
 };
 use borsh::BorshDeserialize;
 use solana_program::{
@@ -13,49 +25,51 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+pub mod accept_authority;
+pub mod accept_bid;
 pub mod admin_claim;
+pub mod approve_proposal;
 pub mod claim;
+pub mod commit_bid;
 pub mod create;
 pub mod create_admin;
+pub mod create_proposal;
 pub mod create_reverse;
 pub mod create_v2;
+pub mod create_v2_batch;
 pub mod end_auction;
 pub mod init;
+pub mod migrate_central_state;
+pub mod place_bid;
+pub mod propose_authority;
+pub mod reclaim_expired;
+pub mod refund_sealed_bid;
+pub mod renew;
 pub mod resell;
 pub mod reset_auction;
+pub mod reveal_bid;
+pub mod reveal_reserve;
+pub mod set_admin_set;
+pub mod set_pause;
+pub mod set_payment_mint;
+pub mod set_price_schedule;
 pub mod take_back;
+pub mod update_blocklist;
+pub mod update_config;
+pub mod withdraw_refund;
 
 ////////////////////////////////////////////////////////////
 
-pub const OVERTIME_LENGTH: u64 = 900;
-pub const PRICE_INCREMENT_MARGIN: u64 = 429496729; // 1% bid increment
-pub const END_AUCTION_GAP: u64 = 600;
+pub const MAX_BATCH_NAMES: usize = 10; // Names registered by a single CreateV2Batch
 pub const TOKEN_MINT: Pubkey = pubkey!("EchesyfXePKdLtoiZSL8pBe8Myagyy8ZRqsACNCFGnvp"); // FIDA mint
-pub const MINIMUM_PRICE_USD: u64 = 20_000_000; // 20 USD
-pub const AUCTION_PROGRAM_ID: Pubkey = pubkey!("AVWV7vdWbLqXiLKFaP19GhYurhwxaLp2qRBSjT5tR5vT");
-pub const BONFIDA_FIDA_VAULT: Pubkey = pubkey!("AUoZ3YAhV3b2rZeEH93UMZHXUZcTramBvb4d9YEVySkc");
-pub const AUCTION_MAX_LENGTH: u64 = 259200; // 3 days in seconds
-pub const ADMIN: Pubkey = pubkey!("BD4vT1aztHmuEPZh7GgvpeFskgyhi9AtPwtxzYEh5J91");
-pub const FEES: &[u64] = &[500, 300, 200, 150, 100]; // Fees for low leverage orders for tiers [0, 1 ,2]
-pub const FEE_TIERS: [u64; 4] = [10_000_000, 100_000_000, 500_000_000, 1_000_000_000]; // Amount of FIDA tokens (with precision) that the discount account needs to hold
 pub const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
 pub const FIDA_MINT: Pubkey = pubkey!("EchesyfXePKdLtoiZSL8pBe8Myagyy8ZRqsACNCFGnvp");
 pub const ROOT_DOMAIN_ACCOUNT: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
-pub const BONFIDA_SOL_VAULT: Pubkey = pubkey!("GcWEQ9K78FV7LEHteFVciYApERk5YvQuFDQPk1yYJVXi");
-pub const ADMIN_CREATE_KEY: Pubkey = pubkey!("CHG6XM8Ugk7xkZMcp5PMoJTYfwCG7XStv5rt2w1eQKPS");
-pub const BONFIDA_USDC_VAULT: Pubkey = pubkey!("DmSyHDSM9eSLyvoLsPvDr5fRRFZ7Bfr3h3ULvWpgQaq7");
-pub const ADMIN_CLAIM_KEY: Pubkey = pubkey!("VBx642K1hYGLU5Zm1CHW1uRXAtFgxN5mRqyMcXnLZFW");
-pub const PYTH_FIDA_PRICE_ACC: Pubkey = pubkey!("ETp9eKXVv1dWwHSpsXRUuXHmw24PwRkttCGVgpZEY9zF");
-pub const CENTRAL_STATE: Pubkey = pubkey!("33m47vH6Eav6jr5Ry86XjhRft2jRBLDnDgPSHoquXi2Z");
 
-// Fees taken for the reselling of domain names
-// | Tier | Percentage of payout    | Requirements   |
-// | ---- | ----------------------- | -------------- |
-// | 0    | 5%                      | None           |
-// | 1    | 3%                      | 10 FIDA        |
-// | 2    | 2%                      | 100 FIDA       |
-// | 3    | 1.5%                    | 500 FIDA       |
-// | 4    | 1%                      | 1,000 FIDA     |
+// Vaults, fees and auction parameters are stored in the central state (see `state::ProgramConfig`)
+// and can be changed with the `UpdateConfig` instruction. Admin keys are rotated with the
+// `ProposeAuthority` and `AcceptAuthority` instructions. User facing instructions can be paused
+// with the `Pause` and `Unpause` instructions.
 
 ////////////////////////////////////////////////////////////
 
@@ -72,14 +86,36 @@ impl Processor {
             .map_err(|_| ProgramError::InvalidInstructionData)?;
         msg!("Instruction unpacked");
 
+        if let Some(flag) = instruction.pause_flag() {
+            check_not_paused(program_id, accounts, flag)?;
+        }
+
         match instruction {
-            ProgramInstruction::Init { state_nonce } => {
+            ProgramInstruction::Init {
+                state_nonce,
+                admin,
+                admin_claim_key,
+                admin_create_key,
+                config,
+            } => {
                 msg!("Instruction: Init");
-                process_init(program_id, accounts, state_nonce)?;
+                process_init(
+                    program_id,
+                    accounts,
+                    state_nonce,
+                    admin,
+                    admin_claim_key,
+                    admin_create_key,
+                    config,
+                )?;
             }
-            ProgramInstruction::Create { name } => {
+            ProgramInstruction::Create {
+                name,
+                sealed_bid,
+                second_price,
+            } => {
                 msg!("Instruction: Create");
-                process_create(program_id, accounts, name)?;
+                process_create(program_id, accounts, name, sealed_bid, second_price)?;
             }
             ProgramInstruction::Claim { hashed_name, space } => {
                 msg!("Instruction: Claim");
@@ -94,6 +130,10 @@ impl Processor {
                 minimum_price,
                 end_auction_at,
                 max_price,
+                end_auction_gap,
+                bid_increment_bps,
+                second_price,
+                reserve_commitment,
             } => {
                 msg!("Instruction: Resell");
                 process_resell(
@@ -103,6 +143,10 @@ impl Processor {
                     minimum_price,
                     end_auction_at,
                     max_price,
+                    end_auction_gap,
+                    bid_increment_bps,
+                    second_price,
+                    reserve_commitment,
                 )?;
             }
             ProgramInstruction::CreateReverse { name } => {
@@ -116,18 +160,118 @@ impl Processor {
             }
             ProgramInstruction::ClaimAdmin { hashed_name, space } => {
                 msg!("Instruction: A Claim");
-                process_a_claim(program_id, accounts, hashed_name.to_vec(), space)?;
+                process_a_claim(program_id, accounts, hashed_name, space)?;
             }
             ProgramInstruction::EndAuction { name } => {
                 msg!("Instruction: End Auction");
                 process_end_auction(program_id, accounts, name)?;
             }
-            ProgramInstruction::CreateV2 { name, space } => {
+            ProgramInstruction::CreateV2 {
+                name,
+                space,
+                premium_proofs,
+                voucher,
+            } => {
                 msg!("Instruction: Create v2");
-                process_create_v2(program_id, accounts, name, space)?;
+                process_create_v2(program_id, accounts, name, space, premium_proofs, voucher)?;
+            }
+            ProgramInstruction::TakeBack { reason } => {
+                msg!("Instruction: Take back");
+                process_take_back(program_id, accounts, reason)?;
+            }
+            ProgramInstruction::UpdateConfig { config } => {
+                msg!("Instruction: Update config");
+                process_update_config(program_id, accounts, config)?;
+            }
+            ProgramInstruction::SetAdminSet { admins, threshold } => {
+                msg!("Instruction: Set admin set");
+                process_set_admin_set(program_id, accounts, admins, threshold)?;
+            }
+            ProgramInstruction::CreateProposal { action } => {
+                msg!("Instruction: Create proposal");
+                process_create_proposal(program_id, accounts, action)?;
+            }
+            ProgramInstruction::ApproveProposal => {
+                msg!("Instruction: Approve proposal");
+                process_approve_proposal(program_id, accounts)?;
+            }
+            ProgramInstruction::ProposeAuthority { role, new_key } => {
+                msg!("Instruction: Propose authority");
+                process_propose_authority(program_id, accounts, role, new_key)?;
+            }
+            ProgramInstruction::AcceptAuthority { role } => {
+                msg!("Instruction: Accept authority");
+                process_accept_authority(program_id, accounts, role)?;
+            }
+            ProgramInstruction::Pause { flags } => {
+                msg!("Instruction: Pause");
+                process_set_pause(program_id, accounts, flags, true)?;
+            }
+            ProgramInstruction::Unpause { flags } => {
+                msg!("Instruction: Unpause");
+                process_set_pause(program_id, accounts, flags, false)?;
+            }
+            ProgramInstruction::UpdateBlocklist { page, add, remove } => {
+                msg!("Instruction: Update blocklist");
+                process_update_blocklist(program_id, accounts, page, add, remove)?;
+            }
+            ProgramInstruction::SetPriceSchedule { schedule } => {
+                msg!("Instruction: Set price schedule");
+                process_set_price_schedule(program_id, accounts, schedule)?;
+            }
+            ProgramInstruction::SetPaymentMint { payment_mint } => {
+                msg!("Instruction: Set payment mint");
+                process_set_payment_mint(program_id, accounts, payment_mint)?;
+            }
+            ProgramInstruction::CreateV2Batch {
+                names,
+                premium_proofs,
+            } => {
+                msg!("Instruction: Create v2 batch");
+                process_create_v2_batch(program_id, accounts, names, premium_proofs)?;
+            }
+            ProgramInstruction::Renew { name, years } => {
+                msg!("Instruction: Renew");
+                process_renew(program_id, accounts, name, years)?;
+            }
+            ProgramInstruction::ReclaimExpired => {
+                msg!("Instruction: Reclaim expired");
+                process_reclaim_expired(program_id, accounts)?;
+            }
+            ProgramInstruction::PlaceBid { amount } => {
+                msg!("Instruction: Place bid");
+                process_place_bid(program_id, accounts, amount)?;
+            }
+            ProgramInstruction::CommitBid {
+                commitment,
+                deposit,
+            } => {
+                msg!("Instruction: Commit bid");
+                process_commit_bid(program_id, accounts, commitment, deposit)?;
+            }
+            ProgramInstruction::RevealBid { amount, salt } => {
+                msg!("Instruction: Reveal bid");
+                process_reveal_bid(program_id, accounts, amount, salt)?;
+            }
+            ProgramInstruction::RefundSealedBid => {
+                msg!("Instruction: Refund sealed bid");
+                process_refund_sealed_bid(program_id, accounts)?;
+            }
+            ProgramInstruction::RevealReserve { reserve, salt } => {
+                msg!("Instruction: Reveal reserve");
+                process_reveal_reserve(program_id, accounts, reserve, salt)?;
+            }
+            ProgramInstruction::AcceptBid => {
+                msg!("Instruction: Accept bid");
+                process_accept_bid(program_id, accounts)?;
+            }
+            ProgramInstruction::MigrateCentralState => {
+                msg!("Instruction: Migrate central state");
+                process_migrate_central_state(program_id, accounts)?;
             }
-            ProgramInstruction::TakeBack => {
-                process_take_back(program_id, accounts)?;
+            ProgramInstruction::WithdrawRefund => {
+                msg!("Instruction: Withdraw refund");
+                process_withdraw_refund(program_id, accounts)?;
             }
         }
         Ok(())
@@ -0,0 +1,78 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    state::{AuthorityRole, CentralState},
+    utils::{check_account_owner, check_signer},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    new_authority: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        new_authority: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+    };
+
+    // Check ownership
+    check_account_owner(a.central_state, program_id)?;
+
+    // Check signer
+    check_signer(a.new_authority)?;
+
+    Ok(a)
+}
+
+pub fn process_accept_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+
+    let position = central_state
+        .pending_authorities
+        .iter()
+        .position(|p| p.role == role)
+        .ok_or_else(|| {
+            msg!("No authority change is pending for this role");
+            ProgramError::InvalidArgument
+        })?;
+    let pending = central_state.pending_authorities.remove(position);
+
+    if pending.new_key != accounts.new_authority.key.to_bytes() {
+        msg!("The pending authority change was proposed for another key");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < pending.effective_at {
+        msg!(
+            "The authority change only becomes effective at {}",
+            pending.effective_at
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    *central_state.authority_mut(role) = pending.new_key;
+    central_state.pack_into_slice(&mut accounts.central_state.data.borrow_mut());
+
+    Ok(())
+}
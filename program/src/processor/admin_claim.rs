@@ -1,70 +1,96 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
     clock::Clock,
     entrypoint::ProgramResult,
     msg,
-    program::invoke_signed,
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
-    sysvar::Sysvar,
+    system_program,
+    sysvar::{self, Sysvar},
 };
-use spl_auction::{instruction::close_auction_pot, processor::AuctionData};
-use spl_name_service::state::NameRecordHeader;
+use spl_name_service::state::{get_seeds_and_key, NameRecordHeader};
 
-use super::ADMIN_CLAIM_KEY;
+use super::ROOT_DOMAIN_ACCOUNT;
 use crate::{
+    auction::{credit_refund, Auction},
     error::NameAuctionError,
-    utils::{check_account_key, check_signer, Cpi},
+    state::{AdminAction, CentralState, NameAuction, NameAuctionStatus, REGISTRATION_PERIOD},
+    utils::{
+        check_account_key, check_account_owner, check_signer, execute_proposal, set_registration,
+        Cpi,
+    },
 };
 
 struct Accounts<'a, 'b: 'a> {
     clock_sysvar: &'a AccountInfo<'b>,
     spl_token_program: &'a AccountInfo<'b>,
     naming_service_program: &'a AccountInfo<'b>,
+    root_domain: &'a AccountInfo<'b>,
     name: &'a AccountInfo<'b>,
     system_program: &'a AccountInfo<'b>,
     auction: &'a AccountInfo<'b>,
     central_state: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
-    auction_program: &'a AccountInfo<'b>,
-    bidder_wallet: &'a AccountInfo<'b>,
-    bidder_pot: &'a AccountInfo<'b>,
-    bidder_pot_token: &'a AccountInfo<'b>,
+    auction_pot: &'a AccountInfo<'b>,
     bonfida_vault: &'a AccountInfo<'b>,
-    admin_signer: &'a AccountInfo<'b>,
     new_name_owner: &'a AccountInfo<'b>,
     fee_payer: &'a AccountInfo<'b>,
-    root_domain: &'a AccountInfo<'b>,
+    registration: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+    top_bid_refund: Option<&'a AccountInfo<'b>>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
     accounts: &'a [AccountInfo<'b>],
+    hashed_name: [u8; 32],
+    space: u32,
 ) -> Result<Accounts<'a, 'b>, ProgramError> {
     let accounts_iter = &mut accounts.iter();
     let a = Accounts {
         clock_sysvar: next_account_info(accounts_iter)?,
         spl_token_program: next_account_info(accounts_iter)?,
         naming_service_program: next_account_info(accounts_iter)?,
+        root_domain: next_account_info(accounts_iter)?,
         name: next_account_info(accounts_iter)?,
         system_program: next_account_info(accounts_iter)?,
-        auction_program: next_account_info(accounts_iter)?,
         auction: next_account_info(accounts_iter)?,
         central_state: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
-        bidder_wallet: next_account_info(accounts_iter)?,
-        bidder_pot: next_account_info(accounts_iter)?,
-        bidder_pot_token: next_account_info(accounts_iter)?,
+        auction_pot: next_account_info(accounts_iter)?,
         bonfida_vault: next_account_info(accounts_iter)?,
-        admin_signer: next_account_info(accounts_iter)?,
         new_name_owner: next_account_info(accounts_iter)?,
         fee_payer: next_account_info(accounts_iter)?,
-        root_domain: next_account_info(accounts_iter)?,
+        registration: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+        top_bid_refund: next_account_info(accounts_iter).ok(),
     };
-    check_signer(a.admin_signer).unwrap();
-    check_account_key(a.admin_signer, &ADMIN_CLAIM_KEY).unwrap();
+    check_account_key(a.clock_sysvar, &sysvar::clock::id()).unwrap();
+    check_account_key(a.spl_token_program, &spl_token::id()).unwrap();
+    check_account_key(a.naming_service_program, &spl_name_service::id()).unwrap();
+    check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
+    check_account_key(a.system_program, &system_program::id()).unwrap();
+    check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
+    check_account_owner(a.central_state, program_id).unwrap();
+    check_account_owner(a.state, program_id).unwrap();
+    check_signer(a.fee_payer).unwrap();
+
+    execute_proposal(
+        program_id,
+        a.admin_set,
+        a.proposal,
+        &AdminAction::ClaimAdmin {
+            hashed_name,
+            space,
+            new_owner: a.new_name_owner.key.to_bytes(),
+        },
+    )?;
 
     Ok(a)
 }
@@ -72,92 +98,113 @@ fn parse_accounts<'a, 'b: 'a>(
 pub fn process_a_claim(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    hashed_name: Vec<u8>,
+    hashed_name: [u8; 32],
     space: u32,
 ) -> ProgramResult {
-    // let accounts = parse_accounts(accounts)?;
-
-    // let (derived_state_key, _) =
-    //     Pubkey::find_program_address(&[&accounts.name.key.to_bytes()], program_id);
-    // if &derived_state_key != accounts.state.key {
-    //     msg!("An invalid signer account was provided");
-    //     return Err(ProgramError::InvalidArgument);
-    // }
-
-    // let central_state_nonce = accounts.central_state.data.borrow()[0];
-    // let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
-
-    // let lamports = Rent::get()?.minimum_balance(space as usize + NameRecordHeader::LEN);
-    // if accounts.name.data_is_empty() {
-    //     msg!("Name account does not exist. Creating.");
-    //     Cpi::create_name_account(
-    //         accounts.naming_service_program,
-    //         accounts.system_program,
-    //         accounts.name,
-    //         accounts.fee_payer,
-    //         accounts.new_name_owner,
-    //         accounts.root_domain,
-    //         accounts.central_state,
-    //         hashed_name,
-    //         lamports,
-    //         space,
-    //         central_state_signer_seeds,
-    //     )?;
-    // } else {
-    //     Cpi::transfer_name_account(
-    //         accounts.naming_service_program,
-    //         accounts.central_state,
-    //         accounts.name,
-    //         accounts.new_name_owner.key,
-    //         Some(central_state_signer_seeds),
-    //     )?;
-    // }
-
-    // let auction: AuctionData = try_from_slice_unchecked(&accounts.auction.data.borrow()).unwrap();
-    // let clock = Clock::from_account_info(accounts.clock_sysvar).unwrap();
-
-    // if !auction.ended(clock.unix_timestamp).unwrap() {
-    //     msg!("The auction must have ended to reclaim");
-    //     return Err(NameAuctionError::AuctionInProgress.into());
-    // }
-
-    // if let spl_auction::processor::BidState::EnglishAuction { bids, max: _ } = auction.bid_state {
-    //     if bids.is_empty() {
-    //         msg!("The auction has no bidder and cannot be a reclaimed!");
-    //         return Err(ProgramError::InvalidArgument);
-    //     }
-    // } else {
-    //     unreachable!()
-    // }
-
-    // let clean_up_instr = close_auction_pot(
-    //     *accounts.auction_program.key,
-    //     *accounts.auction.key,
-    //     *accounts.bidder_pot.key,
-    //     *accounts.bidder_wallet.key,
-    //     *accounts.bonfida_vault.key,
-    //     *accounts.system_program.key,
-    //     *accounts.central_state.key,
-    //     *accounts.bonfida_vault.key,
-    //     *accounts.bidder_pot_token.key,
-    //     *accounts.name.key,
-    // );
-    // invoke_signed(
-    //     &clean_up_instr,
-    //     &[
-    //         accounts.auction_program.clone(),
-    //         accounts.auction.clone(),
-    //         accounts.bidder_pot.clone(),
-    //         accounts.bidder_pot_token.clone(),
-    //         accounts.bidder_wallet.clone(),
-    //         accounts.bonfida_vault.clone(),
-    //         accounts.system_program.clone(),
-    //         accounts.central_state.clone(),
-    //         accounts.name.clone(),
-    //         accounts.spl_token_program.clone(),
-    //     ],
-    //     &[central_state_signer_seeds],
-    // )?;
+    let accounts = parse_accounts(program_id, accounts, hashed_name, space)?;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    check_account_key(
+        accounts.bonfida_vault,
+        &Pubkey::new(&central_state.config.bonfida_fida_vault),
+    )
+    .or_else(|_| {
+        check_account_key(
+            accounts.bonfida_vault,
+            &Pubkey::new(&central_state.config.bonfida_usdc_vault),
+        )
+    })?;
+
+    let (name_account_key, _) = get_seeds_and_key(
+        accounts.naming_service_program.key,
+        hashed_name.to_vec(),
+        None,
+        Some(accounts.root_domain.key),
+    );
+    check_account_key(accounts.name, &name_account_key)?;
+
+    let (derived_state_key, _) =
+        Pubkey::find_program_address(&[&name_account_key.to_bytes()], program_id);
+    check_account_key(accounts.state, &derived_state_key)?;
+    let state = NameAuction::unpack_unchecked(&accounts.state.data.borrow())?;
+    if state.status != NameAuctionStatus::FirstAuction || !accounts.name.data_is_empty() {
+        msg!("Only primary auctions can be claimed by the admins");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    check_account_key(accounts.auction, &Pubkey::new(&state.auction_account))?;
+    let mut auction = Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
+    check_account_key(
+        accounts.auction_pot,
+        &Auction::find_pot_key(program_id, &name_account_key).0,
+    )?;
+
+    let clock = Clock::from_account_info(accounts.clock_sysvar)?;
+    if !auction.ended(clock.unix_timestamp) {
+        msg!("The auction must have ended to reclaim");
+        return Err(NameAuctionError::AuctionInProgress.into());
+    }
+    if auction.settled {
+        msg!("The auction has already been claimed");
+        return Err(NameAuctionError::AuctionRealized.into());
+    }
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+
+    // The winning bid is paid out as if the auction had been claimed
+    if let (Some(top_bid), Some(price)) = (&auction.top_bid, auction.clearing_price()) {
+        Cpi::transfer_tokens(
+            accounts.spl_token_program,
+            accounts.auction_pot,
+            accounts.bonfida_vault,
+            accounts.central_state,
+            price,
+            Some(central_state_signer_seeds),
+        )?;
+        if top_bid.amount > price {
+            let top_bid_refund = accounts
+                .top_bid_refund
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            credit_refund(
+                program_id,
+                accounts.system_program,
+                accounts.rent_sysvar,
+                accounts.fee_payer,
+                top_bid_refund,
+                accounts.auction.key,
+                &Pubkey::new(&top_bid.bidder),
+                top_bid.amount - price,
+            )?;
+        }
+    }
+
+    let lamports = Rent::get()?.minimum_balance(space as usize + NameRecordHeader::LEN);
+    Cpi::create_name_account(
+        accounts.naming_service_program,
+        accounts.system_program,
+        accounts.name,
+        accounts.fee_payer,
+        accounts.new_name_owner,
+        accounts.root_domain,
+        accounts.central_state,
+        hashed_name.to_vec(),
+        lamports,
+        space,
+        central_state_signer_seeds,
+    )?;
+    set_registration(
+        program_id,
+        accounts.system_program,
+        accounts.fee_payer,
+        accounts.registration,
+        accounts.rent_sysvar,
+        &name_account_key,
+        clock.unix_timestamp + REGISTRATION_PERIOD,
+    )?;
+
+    auction.settled = true;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
 
     Ok(())
 }
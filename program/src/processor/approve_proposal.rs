@@ -0,0 +1,80 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    borsh::try_from_slice_unchecked,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::NameAuctionError,
+    state::{AdminSet, Proposal},
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    approver: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        approver: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.admin_set, &AdminSet::find_key(program_id).0)?;
+
+    // Check ownership
+    check_account_owner(a.admin_set, program_id)?;
+    check_account_owner(a.proposal, program_id)?;
+
+    // Check signer
+    check_signer(a.approver)?;
+
+    Ok(a)
+}
+
+pub fn process_approve_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let admin_set = AdminSet::unpack_unchecked(&accounts.admin_set.data.borrow())?;
+    if !admin_set.is_admin(accounts.approver.key) {
+        msg!("The approver is not part of the admin set");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut proposal: Proposal = try_from_slice_unchecked(&accounts.proposal.data.borrow())?;
+    check_account_key(
+        accounts.proposal,
+        &Proposal::find_key(program_id, proposal.index).0,
+    )?;
+
+    if proposal.executed {
+        return Err(NameAuctionError::ProposalAlreadyExecuted.into());
+    }
+
+    let approver = accounts.approver.key.to_bytes();
+    if proposal.approvals.contains(&approver) {
+        msg!("The proposal has already been approved by this key");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    proposal.approvals.retain(|k| admin_set.admins.contains(k));
+    proposal.approvals.push(approver);
+
+    let mut pt: &mut [u8] = &mut accounts.proposal.data.borrow_mut();
+    proposal.serialize(&mut pt)?;
+
+    Ok(())
+}
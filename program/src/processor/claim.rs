@@ -1,6 +1,5 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
     clock::Clock,
     entrypoint::ProgramResult,
     msg,
@@ -11,18 +10,18 @@ use solana_program::{
     system_program,
     sysvar::{self, Sysvar},
 };
-use spl_auction::processor::AuctionData;
 use spl_name_service::state::{get_seeds_and_key, NameRecordHeader};
 use spl_token::state::Account;
 
-use super::{
-    AUCTION_PROGRAM_ID, BONFIDA_FIDA_VAULT, BONFIDA_SOL_VAULT, BONFIDA_USDC_VAULT, FEES, FEE_TIERS,
-    FIDA_MINT, ROOT_DOMAIN_ACCOUNT,
-};
+use super::{FIDA_MINT, ROOT_DOMAIN_ACCOUNT};
 use crate::{
+    auction::{credit_refund, Auction},
     error::NameAuctionError,
-    state::{NameAuction, ResellingAuction},
-    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+    state::{CentralState, NameAuction, ResellingAuction, REGISTRATION_PERIOD},
+    utils::{
+        check_account_key, check_account_owner, check_signer, pay_with_referral, set_registration,
+        Cpi,
+    },
 };
 
 struct Accounts<'a, 'b: 'a> {
@@ -36,18 +35,16 @@ struct Accounts<'a, 'b: 'a> {
     central_state: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
     reselling_state: &'a AccountInfo<'b>,
-    auction_program: &'a AccountInfo<'b>,
     fee_payer: &'a AccountInfo<'b>,
-    quote_mint: &'a AccountInfo<'b>,
     destination_token: &'a AccountInfo<'b>,
     bidder_wallet: &'a AccountInfo<'b>,
-    bidder_pot: &'a AccountInfo<'b>,
-    bidder_pot_token: &'a AccountInfo<'b>,
+    auction_pot: &'a AccountInfo<'b>,
     bonfida_vault: &'a AccountInfo<'b>,
     fida_discount: &'a AccountInfo<'b>,
-    buy_now: &'a AccountInfo<'b>,
-    bonfida_sol_vault: &'a AccountInfo<'b>,
-    referrer: Option<&'a AccountInfo<'b>>,
+    registration: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    top_bid_refund: &'a AccountInfo<'b>,
+    referrer: Option<(&'a AccountInfo<'b>, &'a AccountInfo<'b>)>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
@@ -55,46 +52,40 @@ fn parse_accounts<'a, 'b: 'a>(
     accounts: &'a [AccountInfo<'b>],
 ) -> Result<Accounts<'a, 'b>, ProgramError> {
     let accounts_iter = &mut accounts.iter();
-    let a = Accounts {
+    let mut a = Accounts {
         clock_sysvar: next_account_info(accounts_iter)?,
         spl_token_program: next_account_info(accounts_iter)?,
         naming_service_program: next_account_info(accounts_iter)?,
         root_domain: next_account_info(accounts_iter)?,
         name: next_account_info(accounts_iter)?,
         system_program: next_account_info(accounts_iter)?,
-        auction_program: next_account_info(accounts_iter)?,
         auction: next_account_info(accounts_iter)?,
         central_state: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
         reselling_state: next_account_info(accounts_iter)?,
         fee_payer: next_account_info(accounts_iter)?,
-        quote_mint: next_account_info(accounts_iter)?,
         destination_token: next_account_info(accounts_iter)?,
         bidder_wallet: next_account_info(accounts_iter)?,
-        bidder_pot: next_account_info(accounts_iter)?,
-        bidder_pot_token: next_account_info(accounts_iter)?,
+        auction_pot: next_account_info(accounts_iter)?,
         bonfida_vault: next_account_info(accounts_iter)?,
         fida_discount: next_account_info(accounts_iter)?,
-        buy_now: next_account_info(accounts_iter)?,
-        bonfida_sol_vault: next_account_info(accounts_iter)?,
-        referrer: next_account_info(accounts_iter).ok(),
+        registration: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        top_bid_refund: next_account_info(accounts_iter)?,
+        referrer: None,
     };
+    if let Ok(referrer_token) = next_account_info(accounts_iter) {
+        a.referrer = Some((referrer_token, next_account_info(accounts_iter)?));
+    }
     check_account_key(a.clock_sysvar, &sysvar::clock::id()).unwrap();
     check_account_key(a.spl_token_program, &spl_token::id()).unwrap();
     check_account_key(a.naming_service_program, &spl_name_service::id()).unwrap();
     check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
     check_account_key(a.system_program, &system_program::id()).unwrap();
-    check_account_key(a.auction_program, &AUCTION_PROGRAM_ID).unwrap();
-    check_account_owner(a.auction, &AUCTION_PROGRAM_ID).unwrap();
+    check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
     check_account_owner(a.central_state, program_id).unwrap();
     check_account_owner(a.state, program_id).unwrap();
     // check_signer(a.bidder_wallet).unwrap();
-    if a.bonfida_vault.key != &BONFIDA_FIDA_VAULT && a.bonfida_vault.key != &BONFIDA_USDC_VAULT {
-        msg!("Wrong Bonfida vault address");
-        return Err(ProgramError::InvalidArgument);
-    };
-
-    check_account_key(a.bonfida_sol_vault, &BONFIDA_SOL_VAULT).unwrap();
 
     Ok(a)
 }
@@ -107,6 +98,17 @@ pub fn process_claim(
 ) -> ProgramResult {
     let accounts = parse_accounts(program_id, accounts)?;
 
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let bonfida_fida_vault = Pubkey::new(&central_state.config.bonfida_fida_vault);
+    let bonfida_usdc_vault = Pubkey::new(&central_state.config.bonfida_usdc_vault);
+
+    if accounts.bonfida_vault.key != &bonfida_fida_vault
+        && accounts.bonfida_vault.key != &bonfida_usdc_vault
+    {
+        msg!("Wrong Bonfida vault address");
+        return Err(ProgramError::InvalidArgument);
+    };
+
     let (name_account_key, _) = get_seeds_and_key(
         accounts.naming_service_program.key,
         hashed_name.clone(),
@@ -121,28 +123,42 @@ pub fn process_claim(
 
     let state = NameAuction::unpack_unchecked(&accounts.state.data.borrow())?;
 
-    check_account_key(accounts.quote_mint, &Pubkey::new(&state.quote_mint))?;
-
-    let (derived_state_key, derived_signer_nonce) =
+    let (derived_state_key, _) =
         Pubkey::find_program_address(&[&name_account_key.to_bytes()], program_id);
     if &derived_state_key != accounts.state.key {
         msg!("An invalid signer account was provided");
         return Err(ProgramError::InvalidArgument);
     }
 
-    let signer_seeds: &[&[u8]] = &[&name_account_key.to_bytes(), &[derived_signer_nonce]];
+    check_account_key(accounts.auction, &Pubkey::new(&state.auction_account))?;
+    let mut auction = Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
+    check_account_key(
+        accounts.auction_pot,
+        &Auction::find_pot_key(program_id, &name_account_key).0,
+    )?;
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
 
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let clock = Clock::from_account_info(accounts.clock_sysvar).unwrap();
+    if !auction.ended(clock.unix_timestamp) {
+        msg!("The auction must have ended to claim");
+        return Err(NameAuctionError::AuctionInProgress.into());
+    }
+    if auction.settled {
+        msg!("The auction has already been claimed");
+        return Err(NameAuctionError::AuctionRealized.into());
+    }
 
     let lamports = Rent::get()?.minimum_balance(space as usize + NameRecordHeader::LEN);
+    let is_primary = accounts.name.data_is_empty();
     let mut fee_percentage = 0;
-    if accounts.name.data_is_empty() {
+    if is_primary {
         check_signer(accounts.bidder_wallet).unwrap();
-        check_account_key(accounts.destination_token, &BONFIDA_FIDA_VAULT)
-            .or_else(|_| check_account_key(accounts.destination_token, &BONFIDA_USDC_VAULT))
+        check_account_key(accounts.destination_token, &bonfida_fida_vault)
+            .or_else(|_| check_account_key(accounts.destination_token, &bonfida_usdc_vault))
             .unwrap();
+        check_winner(&auction, accounts.bidder_wallet)?;
         Cpi::create_name_account(
             accounts.naming_service_program,
             accounts.system_program,
@@ -156,6 +172,15 @@ pub fn process_claim(
             space,
             central_state_signer_seeds,
         )?;
+        set_registration(
+            program_id,
+            accounts.system_program,
+            accounts.fee_payer,
+            accounts.registration,
+            accounts.rent_sysvar,
+            &name_account_key,
+            clock.unix_timestamp + REGISTRATION_PERIOD,
+        )?;
     } else {
         // Claiming a reselling auction
         let reselling_state =
@@ -174,39 +199,53 @@ pub fn process_claim(
         )
         .unwrap();
 
-        let auction: AuctionData =
-            try_from_slice_unchecked(&accounts.auction.data.borrow()).unwrap();
-        let clock = Clock::from_account_info(accounts.clock_sysvar).unwrap();
-
-        if !auction.ended(clock.unix_timestamp).unwrap() {
-            msg!("The auction must have ended to reclaim");
-            return Err(NameAuctionError::AuctionInProgress.into());
-        }
-
-        if let spl_auction::processor::BidState::EnglishAuction { bids, max: _ } = auction.bid_state
-        {
-            if bids.is_empty() {
-                msg!("The auction has no bidder and can be reclaimed!");
-                let token_destination_account_owner =
-                    spl_token::state::Account::unpack(&accounts.destination_token.data.borrow())?;
-                check_account_key(
-                    accounts.bidder_wallet,
-                    &token_destination_account_owner.owner,
-                )?;
+        let reserve_met = auction.reserve_met(
+            clock.unix_timestamp,
+            central_state.config.reveal_length as i64,
+        )?;
+        if !reserve_met {
+            // The seller has until the end of the accept window to settle at the top bid
+            if auction.top_bid.is_some()
+                && clock.unix_timestamp
+                    < auction.end_auction_at + central_state.config.accept_bid_window as i64
+            {
+                msg!("The seller can still accept the top bid");
+                return Err(NameAuctionError::AuctionInProgress.into());
+            }
+            msg!("The auction has no bidder above the reserve and can be reclaimed!");
+            let token_destination_account_owner =
+                spl_token::state::Account::unpack(&accounts.destination_token.data.borrow())?;
+            check_account_key(
+                accounts.bidder_wallet,
+                &token_destination_account_owner.owner,
+            )?;
 
-                Cpi::transfer_name_account(
-                    accounts.naming_service_program,
-                    accounts.central_state,
-                    accounts.name,
-                    accounts.bidder_wallet.key,
-                    Some(central_state_signer_seeds),
+            if let Some(top_bid) = &auction.top_bid {
+                credit_refund(
+                    program_id,
+                    accounts.system_program,
+                    accounts.rent_sysvar,
+                    accounts.fee_payer,
+                    accounts.top_bid_refund,
+                    accounts.auction.key,
+                    &Pubkey::new(&top_bid.bidder),
+                    top_bid.amount,
                 )?;
-                return Ok(());
             }
-        } else {
-            unreachable!()
+
+            Cpi::transfer_name_account(
+                accounts.naming_service_program,
+                accounts.central_state,
+                accounts.name,
+                accounts.bidder_wallet.key,
+                Some(central_state_signer_seeds),
+            )?;
+            auction.settled = true;
+            auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+            return Ok(());
         }
 
+        check_winner(&auction, accounts.bidder_wallet)?;
         Cpi::transfer_name_account(
             accounts.naming_service_program,
             accounts.central_state,
@@ -215,6 +254,19 @@ pub fn process_claim(
             Some(central_state_signer_seeds),
         )?;
 
+        // Names registered before expiration was introduced start their registration when resold
+        if accounts.registration.data_is_empty() {
+            set_registration(
+                program_id,
+                accounts.system_program,
+                accounts.fee_payer,
+                accounts.registration,
+                accounts.rent_sysvar,
+                &name_account_key,
+                clock.unix_timestamp + REGISTRATION_PERIOD,
+            )?;
+        }
+
         // Calculate fees
         let mut fee_tier = 0;
 
@@ -228,37 +280,91 @@ pub fn process_claim(
                 msg!("The discount account should be a FIDA token account");
                 return Err(ProgramError::InvalidArgument);
             }
-            fee_tier = match FEE_TIERS
+            let fee_tiers = &central_state.config.fee_tiers;
+            fee_tier = match fee_tiers
                 .iter()
                 .position(|&t| discount_data.amount < (t as u64))
             {
                 Some(i) => i,
-                None => FEE_TIERS.len(),
+                None => fee_tiers.len(),
             };
         }
 
-        fee_percentage = FEES[fee_tier];
+        fee_percentage = central_state.config.fees[fee_tier];
     }
 
-    Cpi::claim_auction(
+    // Second price auctions refund the winner what it bid above the clearing price
+    let top_bid = auction.top_bid.as_ref().unwrap();
+    let amount = auction.clearing_price().unwrap();
+    if top_bid.amount > amount {
+        credit_refund(
+            program_id,
+            accounts.system_program,
+            accounts.rent_sysvar,
+            accounts.fee_payer,
+            accounts.top_bid_refund,
+            accounts.auction.key,
+            &Pubkey::new(&top_bid.bidder),
+            top_bid.amount - amount,
+        )?;
+    }
+
+    // Primary auctions are paid to Bonfida in full, sellers pay a fee on secondary auctions
+    let fee = if is_primary {
+        amount
+    } else {
+        ((amount as u128) * (fee_percentage as u128) / 10_000) as u64
+    };
+    let bonfida_vault = if is_primary {
+        accounts.destination_token
+    } else {
+        accounts.bonfida_vault
+    };
+
+    pay_with_referral(
+        program_id,
         accounts.spl_token_program,
-        accounts.auction_program,
-        accounts.clock_sysvar,
-        accounts.auction,
-        accounts.destination_token,
-        accounts.bidder_wallet,
-        accounts.bidder_pot,
-        accounts.bidder_pot_token,
-        accounts.quote_mint,
-        accounts.state,
-        accounts.bonfida_vault,
-        accounts.buy_now,
-        accounts.bonfida_sol_vault,
+        accounts.system_program,
+        accounts.rent_sysvar,
+        accounts.fee_payer,
+        accounts.bidder_wallet.key,
+        accounts.auction_pot,
+        accounts.central_state,
+        Some(central_state_signer_seeds),
+        bonfida_vault,
         accounts.referrer,
-        *accounts.name.key,
-        signer_seeds,
-        fee_percentage,
+        fee,
+        1,
+        &central_state.config,
     )?;
 
+    if amount > fee {
+        Cpi::transfer_tokens(
+            accounts.spl_token_program,
+            accounts.auction_pot,
+            accounts.destination_token,
+            accounts.central_state,
+            amount - fee,
+            Some(central_state_signer_seeds),
+        )?;
+    }
+
+    auction.settled = true;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
     Ok(())
 }
+
+fn check_winner(auction: &Auction, bidder_wallet: &AccountInfo) -> ProgramResult {
+    match &auction.top_bid {
+        Some(bid) if bidder_wallet.key.to_bytes() == bid.bidder => Ok(()),
+        Some(_) => {
+            msg!("The bidder did not win the auction");
+            Err(ProgramError::InvalidArgument)
+        }
+        None => {
+            msg!("The auction has no bidder");
+            Err(ProgramError::InvalidArgument)
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    auction::{Auction, SealedBid},
+    error::NameAuctionError,
+    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    system_program: &'a AccountInfo<'b>,
+    spl_token_program: &'a AccountInfo<'b>,
+    auction: &'a AccountInfo<'b>,
+    pot: &'a AccountInfo<'b>,
+    sealed_bid: &'a AccountInfo<'b>,
+    bidder: &'a AccountInfo<'b>,
+    bidder_token_source: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        system_program: next_account_info(accounts_iter)?,
+        spl_token_program: next_account_info(accounts_iter)?,
+        auction: next_account_info(accounts_iter)?,
+        pot: next_account_info(accounts_iter)?,
+        sealed_bid: next_account_info(accounts_iter)?,
+        bidder: next_account_info(accounts_iter)?,
+        bidder_token_source: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.system_program, &system_program::ID)?;
+    check_account_key(a.spl_token_program, &spl_token::ID)?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::ID)?;
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+
+    // Check signer
+    check_signer(a.bidder)?;
+
+    Ok(a)
+}
+
+pub fn process_commit_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    commitment: [u8; 32],
+    deposit: u64,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let name_account = Pubkey::new(&auction.resource);
+    check_account_key(
+        accounts.auction,
+        &Auction::find_key(program_id, &name_account).0,
+    )?;
+    check_account_key(
+        accounts.pot,
+        &Auction::find_pot_key(program_id, &name_account).0,
+    )?;
+
+    auction.commit_bid(Clock::get()?.unix_timestamp)?;
+    if deposit < auction.minimum_price {
+        msg!(
+            "The deposit must cover the minimum price of {}",
+            auction.minimum_price
+        );
+        return Err(NameAuctionError::BidTooLow.into());
+    }
+
+    let (sealed_bid_key, sealed_bid_nonce) =
+        SealedBid::find_key(program_id, accounts.auction.key, accounts.bidder.key);
+    check_account_key(accounts.sealed_bid, &sealed_bid_key)?;
+    if !accounts.sealed_bid.data_is_empty() {
+        msg!("A bid has already been committed to this auction");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Cpi::create_account(
+        program_id,
+        accounts.system_program,
+        accounts.bidder,
+        accounts.sealed_bid,
+        accounts.rent_sysvar,
+        &[
+            SealedBid::SEED,
+            &accounts.auction.key.to_bytes(),
+            &accounts.bidder.key.to_bytes(),
+            &[sealed_bid_nonce],
+        ],
+        SealedBid::LEN,
+    )?;
+
+    Cpi::transfer_tokens(
+        accounts.spl_token_program,
+        accounts.bidder_token_source,
+        accounts.pot,
+        accounts.bidder,
+        deposit,
+        None,
+    )?;
+
+    let sealed_bid = SealedBid {
+        auction: accounts.auction.key.to_bytes(),
+        bidder: accounts.bidder.key.to_bytes(),
+        commitment,
+        deposit,
+        revealed: false,
+    };
+    sealed_bid.pack_into_slice(&mut accounts.sealed_bid.data.borrow_mut());
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
+    Ok(())
+}
@@ -1,8 +1,7 @@
-use bonfida_utils::{fp_math::fp32_div, pyth::get_oracle_price_fp32};
+use bonfida_utils::fp_math::fp32_div;
 use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
     clock::Clock,
     entrypoint::ProgramResult,
     hash::hashv,
@@ -13,19 +12,20 @@ use solana_program::{
     system_program,
     sysvar::{self, Sysvar},
 };
-use spl_auction::processor::{AuctionData, BidState};
 use spl_name_service::state::{get_seeds_and_key, HASH_PREFIX};
 
 use crate::{
+    auction::{create_auction_accounts, Auction},
     error::NameAuctionError,
-    processor::MINIMUM_PRICE_USD,
-    state::{NameAuction, NameAuctionStatus},
-    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+    oracle::get_oracle_price_fp32,
+    state::{CentralState, NameAuction, NameAuctionStatus},
+    utils::{
+        check_account_key, check_account_owner, check_not_blocked, check_signer, get_grapheme_len,
+        get_usd_price, Cpi,
+    },
 };
 
-use super::{
-    AUCTION_MAX_LENGTH, AUCTION_PROGRAM_ID, FIDA_MINT, PYTH_FIDA_PRICE_ACC, ROOT_DOMAIN_ACCOUNT,
-};
+use super::{FIDA_MINT, ROOT_DOMAIN_ACCOUNT};
 
 struct Accounts<'a, 'b: 'a> {
     rent_sysvar: &'a AccountInfo<'b>,
@@ -38,10 +38,14 @@ struct Accounts<'a, 'b: 'a> {
     auction: &'a AccountInfo<'b>,
     central_state: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
-    auction_program: &'a AccountInfo<'b>,
+    spl_token_program: &'a AccountInfo<'b>,
     fee_payer: &'a AccountInfo<'b>,
     quote_mint: &'a AccountInfo<'b>,
     pyth_fida_price_acc: &'a AccountInfo<'b>,
+    blocklist_page: &'a AccountInfo<'b>,
+    price_schedule: &'a AccountInfo<'b>,
+    pyth_fida_fallback_price_acc: &'a AccountInfo<'b>,
+    auction_pot: &'a AccountInfo<'b>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
@@ -57,13 +61,17 @@ fn parse_accounts<'a, 'b: 'a>(
         name: next_account_info(accounts_iter)?,
         reverse_lookup: next_account_info(accounts_iter)?,
         system_program: next_account_info(accounts_iter)?,
-        auction_program: next_account_info(accounts_iter)?,
+        spl_token_program: next_account_info(accounts_iter)?,
         auction: next_account_info(accounts_iter)?,
         central_state: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
         fee_payer: next_account_info(accounts_iter)?,
         quote_mint: next_account_info(accounts_iter)?,
         pyth_fida_price_acc: next_account_info(accounts_iter)?,
+        blocklist_page: next_account_info(accounts_iter)?,
+        price_schedule: next_account_info(accounts_iter)?,
+        pyth_fida_fallback_price_acc: next_account_info(accounts_iter)?,
+        auction_pot: next_account_info(accounts_iter)?,
     };
 
     check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
@@ -72,15 +80,13 @@ fn parse_accounts<'a, 'b: 'a>(
     check_account_owner(a.root_domain, &spl_name_service::id()).unwrap();
     check_account_key(a.system_program, &system_program::id()).unwrap();
     check_account_owner(a.central_state, program_id).unwrap();
-    check_account_key(a.auction_program, &AUCTION_PROGRAM_ID).unwrap();
-    // check_account_owner(a.auction, &spl_auction::id()).unwrap();
+    check_account_key(a.spl_token_program, &spl_token::ID).unwrap();
     check_account_owner(a.state, &system_program::id())
         .or_else(|_| check_account_owner(a.state, program_id))
         .unwrap();
     check_signer(a.fee_payer).unwrap();
     check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
     check_account_key(a.quote_mint, &FIDA_MINT).unwrap();
-    check_account_key(a.pyth_fida_price_acc, &PYTH_FIDA_PRICE_ACC).unwrap();
 
     Ok(a)
 }
@@ -89,9 +95,21 @@ pub fn process_create(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     name: String,
+    sealed_bid: bool,
+    second_price: bool,
 ) -> ProgramResult {
     let accounts = parse_accounts(program_id, accounts)?;
 
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    check_account_key(
+        accounts.pyth_fida_price_acc,
+        &Pubkey::new(&central_state.config.pyth_fida_price_acc),
+    )?;
+    check_account_key(
+        accounts.pyth_fida_fallback_price_acc,
+        &Pubkey::new(&central_state.config.pyth_fida_fallback_price_acc),
+    )?;
+
     if name != name.trim().to_lowercase() {
         msg!("Domain names must be lower case and have no space");
         return Err(ProgramError::InvalidArgument);
@@ -106,6 +124,8 @@ pub fn process_create(
         return Err(ProgramError::InvalidArgument);
     }
 
+    check_not_blocked(program_id, accounts.blocklist_page, &hashed_name)?;
+
     let (name_account_key, _) = get_seeds_and_key(
         accounts.naming_service_program.key,
         hashed_name,
@@ -143,29 +163,18 @@ pub fn process_create(
             return Err(ProgramError::InvalidArgument);
         }
         let current_timestamp = Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp;
-        let auction: AuctionData =
-            try_from_slice_unchecked(&accounts.auction.data.borrow()).unwrap();
-        if !auction.ended(current_timestamp)? {
+        let mut auction =
+            Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
+        if !auction.ended(current_timestamp) {
             msg!("The auction has to end before it can be restarted!");
             return Err(NameAuctionError::AuctionInProgress.into());
         }
-        match auction.bid_state {
-            BidState::EnglishAuction { bids, max: _ } => {
-                if !bids.is_empty() {
-                    msg!("The auction has a bidder, which means it has a winner and cannot be reset!");
-                    return Err(NameAuctionError::AuctionRealized.into());
-                }
-            }
-            _ => unreachable!(),
-        };
-        Cpi::start_auction(
-            accounts.auction_program,
-            accounts.clock_sysvar,
-            accounts.auction,
-            accounts.state,
-            *accounts.name.key,
-            signer_seeds,
-        )?;
+        if auction.top_bid.is_some() {
+            msg!("The auction has a bidder, which means it has a winner and cannot be reset!");
+            return Err(NameAuctionError::AuctionRealized.into());
+        }
+        auction.start(current_timestamp)?;
+        auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
         return Ok(());
     }
 
@@ -191,9 +200,8 @@ pub fn process_create(
         return Err(ProgramError::InvalidArgument);
     }
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
-
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
 
     Cpi::create_account(
         program_id,
@@ -204,7 +212,6 @@ pub fn process_create(
         signer_seeds,
         NameAuction::LEN,
     )?;
-    let end_auction_at = Some(AUCTION_MAX_LENGTH);
 
     let state = NameAuction {
         status: NameAuctionStatus::FirstAuction,
@@ -220,43 +227,50 @@ pub fn process_create(
 
     msg!("Setting up auction");
 
-    let min_price_fida = fp32_div(
-        MINIMUM_PRICE_USD,
-        {
-            #[cfg(feature = "mock-oracle")]
-            {
-                5 << 32
-            }
-            #[cfg(not(feature = "mock-oracle"))]
-            get_oracle_price_fp32(&accounts.pyth_fida_price_acc.data.borrow(), 6, 6).unwrap()
-        }, // Fida and USD have 6 decimals
-    )
-    .unwrap();
-    Cpi::create_auction(
-        accounts.auction_program,
-        accounts.rent_sysvar,
+    let min_price_usd =
+        get_usd_price(program_id, accounts.price_schedule, get_grapheme_len(&name))?;
+    let fida_price = get_oracle_price_fp32(
+        program_id,
+        accounts.pyth_fida_price_acc,
+        Some(accounts.pyth_fida_fallback_price_acc),
+        6,
+        6,
+        &central_state.config,
+    )?; // Fida and USD have 6 decimals
+    let min_price_fida =
+        fp32_div(min_price_usd, fida_price).ok_or(ProgramError::InvalidArgument)?;
+    create_auction_accounts(
+        program_id,
         accounts.system_program,
-        accounts.auction,
+        accounts.spl_token_program,
+        accounts.rent_sysvar,
         accounts.fee_payer,
-        end_auction_at,
-        accounts.state,
-        None,
-        *accounts.name.key,
-        min_price_fida,
-        signer_seeds,
-        None,
+        accounts.auction,
+        accounts.auction_pot,
+        accounts.quote_mint,
+        accounts.central_state,
+        &name_account_key,
     )?;
 
     msg!("Starting auction");
 
-    Cpi::start_auction(
-        accounts.auction_program,
-        accounts.clock_sysvar,
-        accounts.auction,
-        accounts.state,
-        *accounts.name.key,
-        signer_seeds,
-    )?;
+    let mut auction = Auction::new(
+        &name_account_key,
+        accounts.quote_mint.key,
+        min_price_fida,
+        None,
+        central_state.config.auction_max_length as i64,
+        central_state.config.end_auction_gap as i64,
+        central_state.config.bid_increment_bps,
+    );
+    if sealed_bid {
+        auction = auction.with_sealed_bids(central_state.config.reveal_length as i64);
+    }
+    if second_price {
+        auction = auction.with_second_price();
+    }
+    auction.start(Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp)?;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
 
     if accounts.reverse_lookup.data_len() == 0 {
         Cpi::create_reverse_lookup_account(
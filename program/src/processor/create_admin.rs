@@ -3,6 +3,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     hash::hashv,
     msg,
@@ -16,13 +17,13 @@ use solana_program::{
 use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
 
 use crate::{
-    processor::{ADMIN_CREATE_KEY, ROOT_DOMAIN_ACCOUNT},
-    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+    processor::ROOT_DOMAIN_ACCOUNT,
+    state::{AdminAction, CentralState, Reservation, MAX_RESERVATION_REASON_LEN},
+    utils::{check_account_key, check_account_owner, check_signer, execute_proposal, Cpi},
 };
 
 struct Accounts<'a, 'b: 'a> {
     fee_payer: &'a AccountInfo<'b>,
-    admin: &'a AccountInfo<'b>,
     naming_service_program: &'a AccountInfo<'b>,
     root_domain: &'a AccountInfo<'b>,
     name: &'a AccountInfo<'b>,
@@ -30,6 +31,10 @@ struct Accounts<'a, 'b: 'a> {
     central_state: &'a AccountInfo<'b>,
     system_program: &'a AccountInfo<'b>,
     rent_sysvar: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+    recipient: &'a AccountInfo<'b>,
+    reservation: &'a AccountInfo<'b>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
@@ -41,7 +46,6 @@ fn parse_accounts<'a, 'b: 'a>(
 
     let accounts = Accounts {
         fee_payer: next_account_info(accounts_iter)?,
-        admin: next_account_info(accounts_iter)?,
         naming_service_program: next_account_info(accounts_iter)?,
         root_domain: next_account_info(accounts_iter)?,
         name: next_account_info(accounts_iter)?,
@@ -49,22 +53,41 @@ fn parse_accounts<'a, 'b: 'a>(
         central_state: next_account_info(accounts_iter)?,
         system_program: next_account_info(accounts_iter)?,
         rent_sysvar: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+        recipient: next_account_info(accounts_iter)?,
+        reservation: next_account_info(accounts_iter)?,
     };
     check_signer(accounts.fee_payer)?;
-    check_signer(accounts.admin)?;
-    check_account_key(accounts.admin, &ADMIN_CREATE_KEY).unwrap();
+    check_account_owner(accounts.central_state, program_id).unwrap();
+    CentralState::from_account_info(accounts.central_state, program_id)?;
     check_account_key(accounts.naming_service_program, &spl_name_service::id()).unwrap();
     check_account_owner(accounts.root_domain, &spl_name_service::id()).unwrap();
     check_account_key(accounts.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
-    check_account_owner(accounts.central_state, program_id).unwrap();
     check_account_key(accounts.system_program, &system_program::id()).unwrap();
     check_account_key(accounts.rent_sysvar, &sysvar::rent::id()).unwrap();
+    check_account_key(accounts.recipient, &Pubkey::new(&params.recipient)).unwrap();
+    check_account_owner(accounts.reservation, &system_program::id()).unwrap();
+    execute_proposal(
+        program_id,
+        accounts.admin_set,
+        accounts.proposal,
+        &AdminAction::CreateAdmin(params.clone()),
+    )?;
 
     if params.name != params.name.trim().to_lowercase() {
         msg!("Domain names must be lower case and have no space");
         return Err(ProgramError::InvalidArgument);
     }
 
+    if params.reason.is_empty() || params.reason.len() > MAX_RESERVATION_REASON_LEN {
+        msg!(
+            "The reservation reason must be between 1 and {} bytes",
+            MAX_RESERVATION_REASON_LEN
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
     let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + &params.name).as_bytes()])
         .as_ref()
         .to_vec();
@@ -107,8 +130,10 @@ fn parse_accounts<'a, 'b: 'a>(
 
 #[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
 pub struct Params {
-    space: u32,
-    name: String,
+    pub space: u32,
+    pub name: String,
+    pub recipient: [u8; 32], // Owner of the reserved name
+    pub reason: String,      // Why and for whom the name is reserved
 }
 
 pub fn process_create_admin(
@@ -116,41 +141,76 @@ pub fn process_create_admin(
     accounts: &[AccountInfo],
     params: Params,
 ) -> ProgramResult {
-    // let (accounts, hashed_name, hashed_reverse_lookup) =
-    //     parse_accounts(program_id, accounts, &params)?;
-
-    // let Params { space, name } = params;
-
-    // let central_state_nonce = accounts.central_state.data.borrow()[0];
-
-    // let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
-
-    // let lamports = Rent::get()?.minimum_balance(space as usize + NameRecordHeader::LEN);
-    // Cpi::create_name_account(
-    //     accounts.naming_service_program,
-    //     accounts.system_program,
-    //     accounts.name,
-    //     accounts.fee_payer,
-    //     accounts.admin,
-    //     accounts.root_domain,
-    //     accounts.central_state,
-    //     hashed_name,
-    //     lamports,
-    //     space,
-    //     central_state_signer_seeds,
-    // )?;
-
-    // Cpi::create_reverse_lookup_account(
-    //     accounts.naming_service_program,
-    //     accounts.system_program,
-    //     accounts.reverse_lookup,
-    //     accounts.fee_payer,
-    //     name,
-    //     hashed_reverse_lookup,
-    //     accounts.central_state,
-    //     accounts.rent_sysvar,
-    //     central_state_signer_seeds,
-    // )?;
+    let (accounts, hashed_name, hashed_reverse_lookup) =
+        parse_accounts(program_id, accounts, &params)?;
+
+    let Params {
+        space,
+        name,
+        recipient,
+        reason,
+    } = params;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+
+    let lamports = Rent::get()?.minimum_balance(space as usize + NameRecordHeader::LEN);
+    Cpi::create_name_account(
+        accounts.naming_service_program,
+        accounts.system_program,
+        accounts.name,
+        accounts.fee_payer,
+        accounts.recipient,
+        accounts.root_domain,
+        accounts.central_state,
+        hashed_name,
+        lamports,
+        space,
+        central_state_signer_seeds,
+    )?;
+
+    Cpi::create_reverse_lookup_account(
+        accounts.naming_service_program,
+        accounts.system_program,
+        accounts.reverse_lookup,
+        accounts.fee_payer,
+        name,
+        hashed_reverse_lookup,
+        accounts.central_state,
+        accounts.rent_sysvar,
+        central_state_signer_seeds,
+        None,
+        None,
+    )?;
+
+    let (reservation_key, reservation_nonce) = Reservation::find_key(program_id, accounts.name.key);
+    check_account_key(accounts.reservation, &reservation_key)?;
+
+    Cpi::create_account(
+        program_id,
+        accounts.system_program,
+        accounts.fee_payer,
+        accounts.reservation,
+        accounts.rent_sysvar,
+        &[
+            Reservation::SEED,
+            &accounts.name.key.to_bytes(),
+            &[reservation_nonce],
+        ],
+        Reservation::space(&reason),
+    )?;
+
+    let reservation = Reservation {
+        name_account: accounts.name.key.to_bytes(),
+        recipient,
+        proposal: accounts.proposal.key.to_bytes(),
+        reserved_at: Clock::get()?.unix_timestamp,
+        reason,
+    };
+    let mut pt: &mut [u8] = &mut accounts.reservation.data.borrow_mut();
+    reservation.serialize(&mut pt)?;
 
     Ok(())
 }
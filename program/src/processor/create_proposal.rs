@@ -0,0 +1,99 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{
+    state::{AdminAction, AdminSet, Proposal},
+    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    proposer: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        proposer: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.admin_set, &AdminSet::find_key(program_id).0)?;
+    check_account_key(a.system_program, &system_program::id())?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+
+    // Check ownership
+    check_account_owner(a.admin_set, program_id)?;
+    check_account_owner(a.proposal, &system_program::id())?;
+
+    // Check signer
+    check_signer(a.proposer)?;
+    check_signer(a.fee_payer)?;
+
+    Ok(a)
+}
+
+pub fn process_create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    action: AdminAction,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut admin_set = AdminSet::unpack_unchecked(&accounts.admin_set.data.borrow())?;
+    if !admin_set.is_admin(accounts.proposer.key) {
+        msg!("The proposer is not part of the admin set");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let index = admin_set.proposal_count;
+    let (proposal_key, nonce) = Proposal::find_key(program_id, index);
+    check_account_key(accounts.proposal, &proposal_key)?;
+
+    Cpi::create_account(
+        program_id,
+        accounts.system_program,
+        accounts.fee_payer,
+        accounts.proposal,
+        accounts.rent_sysvar,
+        &[Proposal::SEED, &index.to_le_bytes(), &[nonce]],
+        Proposal::space(&action),
+    )?;
+
+    // The proposer implicitly approves its own proposal
+    let proposal = Proposal {
+        index,
+        executed: false,
+        approvals: vec![accounts.proposer.key.to_bytes()],
+        action,
+    };
+    {
+        let mut pt: &mut [u8] = &mut accounts.proposal.data.borrow_mut();
+        proposal.serialize(&mut pt)?;
+    }
+
+    admin_set.proposal_count += 1;
+    admin_set.pack_into_slice(&mut accounts.admin_set.data.borrow_mut());
+
+    Ok(())
+}
@@ -11,7 +11,10 @@ use solana_program::{
 };
 use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
 
-use crate::utils::{check_account_key, check_account_owner, check_signer, Cpi};
+use crate::{
+    state::CentralState,
+    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+};
 
 use super::ROOT_DOMAIN_ACCOUNT;
 
@@ -114,9 +117,10 @@ pub fn process_create_reverse(
         return Err(ProgramError::InvalidArgument);
     }
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
 
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
 
     if accounts.reverse_lookup.data_len() == 0 {
         Cpi::create_reverse_lookup_account(
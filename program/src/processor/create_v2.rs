@@ -1,16 +1,22 @@
 use crate::{
-    processor::BONFIDA_FIDA_VAULT,
+    auction::Auction,
+    error::NameAuctionError,
+    state::{
+        CentralState, NameAuction, NameAuctionStatus, PremiumProof, UsedVoucher, Voucher,
+        REGISTRATION_PERIOD,
+    },
     utils::{
-        check_account_key, check_account_owner, check_signer, get_grapheme_len, get_usd_price, Cpi,
+        check_account_key, check_account_owner, check_not_blocked, check_signer, get_grapheme_len,
+        get_premium_price, get_quote_amount, load_payment_mint, load_price_schedule,
+        pay_with_referral, set_registration, verify_voucher, Cpi,
     },
 };
-use bonfida_utils::{fp_math::fp32_div, pyth::get_oracle_price_fp32};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     hash::hashv,
     msg,
-    program::invoke,
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
@@ -19,9 +25,9 @@ use solana_program::{
     sysvar::{self, Sysvar},
 };
 use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
-use spl_token::instruction::transfer;
+use spl_token::state::Mint;
 
-use super::{BONFIDA_USDC_VAULT, PYTH_FIDA_PRICE_ACC, ROOT_DOMAIN_ACCOUNT};
+use super::ROOT_DOMAIN_ACCOUNT;
 
 struct Accounts<'a, 'b: 'a> {
     rent_sysvar: &'a AccountInfo<'b>,
@@ -33,17 +39,28 @@ struct Accounts<'a, 'b: 'a> {
     central_state: &'a AccountInfo<'b>,
     buyer: &'a AccountInfo<'b>,
     buyer_token_source: &'a AccountInfo<'b>,
-    usdc_vault: &'a AccountInfo<'b>,
+    vault: &'a AccountInfo<'b>,
     spl_token_program: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
+    blocklist_page: &'a AccountInfo<'b>,
+    price_schedule: &'a AccountInfo<'b>,
+    auction: &'a AccountInfo<'b>,
+    quote_mint: &'a AccountInfo<'b>,
+    payment_mint: &'a AccountInfo<'b>,
+    pyth_price_acc: &'a AccountInfo<'b>,
+    registration: &'a AccountInfo<'b>,
+    instructions_sysvar: Option<&'a AccountInfo<'b>>,
+    used_voucher: Option<&'a AccountInfo<'b>>,
+    referrer: Option<(&'a AccountInfo<'b>, &'a AccountInfo<'b>)>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
     program_id: &Pubkey,
     accounts: &'a [AccountInfo<'b>],
+    has_voucher: bool,
 ) -> Result<Accounts<'a, 'b>, ProgramError> {
     let accounts_iter = &mut accounts.iter();
-    let a = Accounts {
+    let mut a = Accounts {
         rent_sysvar: next_account_info(accounts_iter)?,
         naming_service_program: next_account_info(accounts_iter)?,
         root_domain: next_account_info(accounts_iter)?,
@@ -53,24 +70,43 @@ fn parse_accounts<'a, 'b: 'a>(
         central_state: next_account_info(accounts_iter)?,
         buyer: next_account_info(accounts_iter)?,
         buyer_token_source: next_account_info(accounts_iter)?,
-        usdc_vault: next_account_info(accounts_iter)?,
+        vault: next_account_info(accounts_iter)?,
         spl_token_program: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
+        blocklist_page: next_account_info(accounts_iter)?,
+        price_schedule: next_account_info(accounts_iter)?,
+        auction: next_account_info(accounts_iter)?,
+        quote_mint: next_account_info(accounts_iter)?,
+        payment_mint: next_account_info(accounts_iter)?,
+        pyth_price_acc: next_account_info(accounts_iter)?,
+        registration: next_account_info(accounts_iter)?,
+        instructions_sysvar: None,
+        used_voucher: None,
+        referrer: None,
     };
+    if has_voucher {
+        a.instructions_sysvar = Some(next_account_info(accounts_iter)?);
+        a.used_voucher = Some(next_account_info(accounts_iter)?);
+    }
+    if let Ok(referrer_token) = next_account_info(accounts_iter) {
+        a.referrer = Some((referrer_token, next_account_info(accounts_iter)?));
+    }
 
     // Check keys
     check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
     check_account_key(a.naming_service_program, &spl_name_service::id()).unwrap();
     check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
     check_account_key(a.system_program, &system_program::id()).unwrap();
-    check_account_key(a.usdc_vault, &BONFIDA_USDC_VAULT).unwrap();
     check_account_key(a.spl_token_program, &spl_token::ID).unwrap();
 
     // Check ownership
     check_account_owner(a.root_domain, &spl_name_service::id()).unwrap();
     check_account_owner(a.central_state, program_id).unwrap();
+    check_account_owner(a.quote_mint, &spl_token::ID).unwrap();
 
-    check_account_owner(a.state, &system_program::id()).unwrap();
+    check_account_owner(a.state, &system_program::id())
+        .or_else(|_| check_account_owner(a.state, program_id))
+        .unwrap();
 
     // Check signer
     check_signer(a.buyer).unwrap();
@@ -83,8 +119,19 @@ pub fn process_create_v2(
     accounts: &[AccountInfo],
     name: String,
     space: u32,
+    premium_proofs: Vec<PremiumProof>,
+    voucher: Option<Voucher>,
 ) -> ProgramResult {
-    let accounts = parse_accounts(program_id, accounts)?;
+    let accounts = parse_accounts(program_id, accounts, voucher.is_some())?;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let payment_mint = load_payment_mint(
+        program_id,
+        accounts.payment_mint,
+        accounts.quote_mint.key,
+        &central_state,
+    )?;
+    check_account_key(accounts.vault, &Pubkey::new(&payment_mint.vault))?;
 
     if name != name.trim().to_lowercase() {
         msg!("Domain names must be lower case and have no space");
@@ -100,6 +147,8 @@ pub fn process_create_v2(
         return Err(ProgramError::InvalidArgument);
     }
 
+    check_not_blocked(program_id, accounts.blocklist_page, &hashed_name)?;
+
     let (name_account_key, _) = get_seeds_and_key(
         accounts.naming_service_program.key,
         hashed_name.clone(),
@@ -115,10 +164,29 @@ pub fn process_create_v2(
         return Err(ProgramError::InvalidArgument);
     }
 
-    if !accounts.state.data_is_empty() {
-        msg!("The name auctioning state account is not empty.");
-        return Err(ProgramError::InvalidArgument);
-    }
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    // A name is released when its first auction ended without bids, it can then be bought directly
+    let released_at = if accounts.state.data_is_empty() {
+        None
+    } else {
+        let state = NameAuction::unpack_unchecked(&accounts.state.data.borrow())?;
+        if state.status != NameAuctionStatus::FirstAuction {
+            msg!("The name auctioning state account is not empty.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        check_account_key(accounts.auction, &Pubkey::new(&state.auction_account))?;
+        let auction = Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
+        if !auction.ended(current_timestamp) {
+            msg!("The auction of this name is still in progress");
+            return Err(NameAuctionError::AuctionInProgress.into());
+        }
+        if auction.top_bid.is_some() {
+            msg!("The auction of this name has a winner");
+            return Err(NameAuctionError::AuctionRealized.into());
+        }
+        Some(auction.end_time())
+    };
 
     if &name_account_key != accounts.name.key {
         msg!("Provided wrong name account");
@@ -147,32 +215,97 @@ pub fn process_create_v2(
         return Err(ProgramError::InvalidArgument);
     }
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
-
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
 
     let grapheme_len = get_grapheme_len(&name);
 
-    let min_price_usdc = get_usd_price(grapheme_len);
+    let price_schedule = load_price_schedule(program_id, accounts.price_schedule)?;
+    let mut price_usd = match get_premium_price(&price_schedule, &hashed_name, &premium_proofs)? {
+        Some(premium_price) => premium_price,
+        None => price_schedule.get_usd_price(grapheme_len),
+    };
+    if let (Some(released_at), Some(release_premium)) =
+        (released_at, &price_schedule.release_premium)
+    {
+        price_usd += release_premium.get_premium(current_timestamp - released_at);
+    }
+
+    if let Some(voucher) = voucher {
+        let (instructions_sysvar, used_voucher) =
+            match (accounts.instructions_sysvar, accounts.used_voucher) {
+                (Some(instructions_sysvar), Some(used_voucher)) => {
+                    (instructions_sysvar, used_voucher)
+                }
+                _ => return Err(ProgramError::NotEnoughAccountKeys),
+            };
+        verify_voucher(
+            instructions_sysvar,
+            &Pubkey::new(&central_state.admin),
+            &voucher,
+        )?;
+        if voucher.discount_percent > 100
+            || voucher.expires_at <= current_timestamp
+            || matches!(voucher.hashed_name, Some(h) if h.as_ref() != hashed_name.as_slice())
+        {
+            msg!("The voucher is expired or not valid for this name");
+            return Err(NameAuctionError::InvalidVoucher.into());
+        }
 
-    // Transfer tokens
-    let transfer_ix = transfer(
-        &spl_token::ID,
-        accounts.buyer_token_source.key,
-        accounts.usdc_vault.key,
-        accounts.buyer.key,
-        &[],
-        min_price_usdc,
+        let (used_voucher_key, used_voucher_nonce) =
+            UsedVoucher::find_key(program_id, voucher.nonce);
+        check_account_key(used_voucher, &used_voucher_key)?;
+        if !used_voucher.data_is_empty() {
+            return Err(NameAuctionError::VoucherAlreadyUsed.into());
+        }
+        Cpi::create_account(
+            program_id,
+            accounts.system_program,
+            accounts.buyer,
+            used_voucher,
+            accounts.rent_sysvar,
+            &[
+                UsedVoucher::SEED,
+                &voucher.nonce.to_le_bytes(),
+                &[used_voucher_nonce],
+            ],
+            UsedVoucher::LEN,
+        )?;
+        UsedVoucher {
+            nonce: voucher.nonce,
+            used_by: accounts.buyer.key.to_bytes(),
+            used_at: current_timestamp,
+        }
+        .pack_into_slice(&mut used_voucher.data.borrow_mut());
+
+        price_usd = price_usd * (100 - voucher.discount_percent as u64) / 100;
+    }
+
+    let mint_decimals = Mint::unpack(&accounts.quote_mint.data.borrow())?.decimals;
+    let price = get_quote_amount(
+        program_id,
+        price_usd,
+        &payment_mint,
+        mint_decimals,
+        accounts.pyth_price_acc,
+        &central_state.config,
     )?;
 
-    invoke(
-        &transfer_ix,
-        &[
-            accounts.spl_token_program.clone(),
-            accounts.buyer_token_source.clone(),
-            accounts.usdc_vault.clone(),
-            accounts.buyer.clone(),
-        ],
+    pay_with_referral(
+        program_id,
+        accounts.spl_token_program,
+        accounts.system_program,
+        accounts.rent_sysvar,
+        accounts.buyer,
+        accounts.buyer.key,
+        accounts.buyer_token_source,
+        accounts.buyer,
+        None,
+        accounts.vault,
+        accounts.referrer,
+        price,
+        1,
+        &central_state.config,
     )?;
 
     // Create domain name
@@ -191,6 +324,16 @@ pub fn process_create_v2(
         central_state_signer_seeds,
     )?;
 
+    set_registration(
+        program_id,
+        accounts.system_program,
+        accounts.buyer,
+        accounts.registration,
+        accounts.rent_sysvar,
+        &name_account_key,
+        current_timestamp + REGISTRATION_PERIOD,
+    )?;
+
     // Reverse look up
     if accounts.reverse_lookup.data_len() == 0 {
         Cpi::create_reverse_lookup_account(
@@ -0,0 +1,275 @@
+use crate::{
+    state::{CentralState, PremiumProof, REGISTRATION_PERIOD},
+    utils::{
+        check_account_key, check_account_owner, check_not_blocked, check_signer, get_grapheme_len,
+        get_premium_price, get_quote_amount, load_payment_mint, load_price_schedule,
+        pay_with_referral, set_registration, Cpi,
+    },
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
+use spl_token::state::Mint;
+
+use super::{MAX_BATCH_NAMES, ROOT_DOMAIN_ACCOUNT};
+
+struct Accounts<'a, 'b: 'a> {
+    rent_sysvar: &'a AccountInfo<'b>,
+    naming_service_program: &'a AccountInfo<'b>,
+    root_domain: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+    buyer: &'a AccountInfo<'b>,
+    buyer_token_source: &'a AccountInfo<'b>,
+    vault: &'a AccountInfo<'b>,
+    spl_token_program: &'a AccountInfo<'b>,
+    price_schedule: &'a AccountInfo<'b>,
+    quote_mint: &'a AccountInfo<'b>,
+    payment_mint: &'a AccountInfo<'b>,
+    pyth_price_acc: &'a AccountInfo<'b>,
+    names: Vec<NameAccounts<'a, 'b>>,
+    referrer: Option<(&'a AccountInfo<'b>, &'a AccountInfo<'b>)>,
+}
+
+struct NameAccounts<'a, 'b: 'a> {
+    name: &'a AccountInfo<'b>,
+    reverse_lookup: &'a AccountInfo<'b>,
+    state: &'a AccountInfo<'b>,
+    blocklist_page: &'a AccountInfo<'b>,
+    registration: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+    names: usize,
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let mut a = Accounts {
+        rent_sysvar: next_account_info(accounts_iter)?,
+        naming_service_program: next_account_info(accounts_iter)?,
+        root_domain: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+        buyer: next_account_info(accounts_iter)?,
+        buyer_token_source: next_account_info(accounts_iter)?,
+        vault: next_account_info(accounts_iter)?,
+        spl_token_program: next_account_info(accounts_iter)?,
+        price_schedule: next_account_info(accounts_iter)?,
+        quote_mint: next_account_info(accounts_iter)?,
+        payment_mint: next_account_info(accounts_iter)?,
+        pyth_price_acc: next_account_info(accounts_iter)?,
+        names: Vec::with_capacity(names),
+        referrer: None,
+    };
+    for _ in 0..names {
+        a.names.push(NameAccounts {
+            name: next_account_info(accounts_iter)?,
+            reverse_lookup: next_account_info(accounts_iter)?,
+            state: next_account_info(accounts_iter)?,
+            blocklist_page: next_account_info(accounts_iter)?,
+            registration: next_account_info(accounts_iter)?,
+        });
+    }
+    if let Ok(referrer_token) = next_account_info(accounts_iter) {
+        a.referrer = Some((referrer_token, next_account_info(accounts_iter)?));
+    }
+
+    // Check keys
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+    check_account_key(a.naming_service_program, &spl_name_service::id())?;
+    check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT)?;
+    check_account_key(a.system_program, &system_program::id())?;
+    check_account_key(a.spl_token_program, &spl_token::ID)?;
+
+    // Check ownership
+    check_account_owner(a.root_domain, &spl_name_service::id())?;
+    check_account_owner(a.central_state, program_id)?;
+    check_account_owner(a.quote_mint, &spl_token::ID)?;
+
+    // Check signer
+    check_signer(a.buyer)?;
+
+    Ok(a)
+}
+
+pub fn process_create_v2_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    names: Vec<(String, u32)>,
+    premium_proofs: Vec<Vec<PremiumProof>>,
+) -> ProgramResult {
+    if names.is_empty() || names.len() > MAX_BATCH_NAMES {
+        msg!("A batch registers 1 to {} names", MAX_BATCH_NAMES);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if (1..names.len()).any(|i| names[..i].iter().any(|(n, _)| n == &names[i].0)) {
+        msg!("A name can only appear once in a batch");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let accounts = parse_accounts(program_id, accounts, names.len())?;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let payment_mint = load_payment_mint(
+        program_id,
+        accounts.payment_mint,
+        accounts.quote_mint.key,
+        &central_state,
+    )?;
+    check_account_key(accounts.vault, &Pubkey::new(&payment_mint.vault))?;
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+    let price_schedule = load_price_schedule(program_id, accounts.price_schedule)?;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let rent = Rent::get()?;
+    let mut total_price_usd: u64 = 0;
+
+    for (i, ((name, space), name_accounts)) in names.into_iter().zip(&accounts.names).enumerate() {
+        if name != name.trim().to_lowercase() {
+            msg!("Domain names must be lower case and have no space");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + &name).as_bytes()])
+            .as_ref()
+            .to_vec();
+
+        check_not_blocked(program_id, name_accounts.blocklist_page, &hashed_name)?;
+
+        let (name_account_key, _) = get_seeds_and_key(
+            accounts.naming_service_program.key,
+            hashed_name.clone(),
+            None,
+            Some(accounts.root_domain.key),
+        );
+        if &name_account_key != name_accounts.name.key {
+            msg!("Provided wrong name account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if name_accounts.name.data_len() != 0 {
+            msg!("Name account is already initialized.");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        // Names released by an auction are sold with a premium through CreateV2
+        let (derived_state_key, _) =
+            Pubkey::find_program_address(&[&name_account_key.to_bytes()], program_id);
+        check_account_key(name_accounts.state, &derived_state_key)?;
+        if !name_accounts.state.data_is_empty() {
+            msg!("The name has been auctioned and cannot be registered in a batch");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let proofs = premium_proofs.get(i).map(Vec::as_slice).unwrap_or(&[]);
+        let price_usd = match get_premium_price(&price_schedule, &hashed_name, proofs)? {
+            Some(premium_price) => premium_price,
+            None => price_schedule.get_usd_price(get_grapheme_len(&name)),
+        };
+        total_price_usd = total_price_usd
+            .checked_add(price_usd)
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        let hashed_reverse_lookup =
+            hashv(&[(HASH_PREFIX.to_owned() + &name_account_key.to_string()).as_bytes()])
+                .as_ref()
+                .to_vec();
+        let (reverse_lookup_account_key, _) = get_seeds_and_key(
+            accounts.naming_service_program.key,
+            hashed_reverse_lookup.clone(),
+            Some(accounts.central_state.key),
+            None,
+        );
+        if &reverse_lookup_account_key != name_accounts.reverse_lookup.key {
+            msg!("Provided wrong reverse lookup account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Create domain name
+        Cpi::create_name_account(
+            accounts.naming_service_program,
+            accounts.system_program,
+            name_accounts.name,
+            accounts.buyer,
+            accounts.buyer,
+            accounts.root_domain,
+            accounts.central_state,
+            hashed_name,
+            rent.minimum_balance(NameRecordHeader::LEN + space as usize),
+            space,
+            central_state_signer_seeds,
+        )?;
+
+        set_registration(
+            program_id,
+            accounts.system_program,
+            accounts.buyer,
+            name_accounts.registration,
+            accounts.rent_sysvar,
+            &name_account_key,
+            current_timestamp + REGISTRATION_PERIOD,
+        )?;
+
+        // Reverse look up
+        if name_accounts.reverse_lookup.data_len() == 0 {
+            Cpi::create_reverse_lookup_account(
+                accounts.naming_service_program,
+                accounts.system_program,
+                name_accounts.reverse_lookup,
+                accounts.buyer,
+                name,
+                hashed_reverse_lookup,
+                accounts.central_state,
+                accounts.rent_sysvar,
+                central_state_signer_seeds,
+                None,
+                None,
+            )?;
+        }
+    }
+
+    let discount_percent = price_schedule.get_volume_discount_percent(accounts.names.len());
+    let price_usd = total_price_usd * (100 - discount_percent as u64) / 100;
+
+    let mint_decimals = Mint::unpack(&accounts.quote_mint.data.borrow())?.decimals;
+    let price = get_quote_amount(
+        program_id,
+        price_usd,
+        &payment_mint,
+        mint_decimals,
+        accounts.pyth_price_acc,
+        &central_state.config,
+    )?;
+
+    // Pay for all the names at once
+    pay_with_referral(
+        program_id,
+        accounts.spl_token_program,
+        accounts.system_program,
+        accounts.rent_sysvar,
+        accounts.buyer,
+        accounts.buyer.key,
+        accounts.buyer_token_source,
+        accounts.buyer,
+        None,
+        accounts.vault,
+        accounts.referrer,
+        price,
+        accounts.names.len() as u64,
+        &central_state.config,
+    )?;
+
+    Ok(())
+}
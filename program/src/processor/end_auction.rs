@@ -1,7 +1,6 @@
-use crate::processor::BONFIDA_SOL_VAULT;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
+    clock::Clock,
     entrypoint::ProgramResult,
     hash::hashv,
     msg,
@@ -10,19 +9,20 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
-    sysvar::{self},
+    system_instruction, system_program,
+    sysvar::{self, Sysvar},
 };
-use spl_auction::processor::{AuctionData, BidState};
 use spl_name_service::state::{get_seeds_and_key, HASH_PREFIX};
 use spl_token::state::Account;
 
 use crate::{
-    state::{NameAuction, NameAuctionStatus, ResellingAuction},
+    auction::{credit_refund, Auction},
+    error::NameAuctionError,
+    state::{CentralState, NameAuction, NameAuctionStatus, ResellingAuction},
     utils::{check_account_key, check_account_owner, check_signer, Cpi},
 };
 
-use super::{AUCTION_PROGRAM_ID, ROOT_DOMAIN_ACCOUNT};
+use super::ROOT_DOMAIN_ACCOUNT;
 
 struct Accounts<'a, 'b: 'a> {
     clock_sysvar: &'a AccountInfo<'b>,
@@ -32,12 +32,21 @@ struct Accounts<'a, 'b: 'a> {
     auction: &'a AccountInfo<'b>,
     central_state: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
-    auction_program: &'a AccountInfo<'b>,
     auction_creator: &'a AccountInfo<'b>,
     reselling_state: &'a AccountInfo<'b>,
     destination_token: &'a AccountInfo<'b>,
     bonfida_sol_vault: &'a AccountInfo<'b>,
     system_program: &'a AccountInfo<'b>,
+    bid_accounts: Option<BidAccounts<'a, 'b>>,
+}
+
+// Only needed to cancel an auction with a bid
+struct BidAccounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    auction_pot: &'a AccountInfo<'b>,
+    top_bid_refund: &'a AccountInfo<'b>,
+    bonfida_vault: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, 'b> {
@@ -45,14 +54,13 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
         program_id: &Pubkey,
         accounts: &'a [AccountInfo<'b>],
         name: String,
-    ) -> Result<(Accounts<'a, 'b>, [u8; 32], u8), ProgramError> {
+    ) -> Result<(Accounts<'a, 'b>, CentralState, Auction), ProgramError> {
         let accounts_iter = &mut accounts.iter();
-        let accounts = Accounts {
+        let mut accounts = Accounts {
             clock_sysvar: next_account_info(accounts_iter)?,
             naming_service_program: next_account_info(accounts_iter)?,
             root_domain: next_account_info(accounts_iter)?,
             name: next_account_info(accounts_iter)?,
-            auction_program: next_account_info(accounts_iter)?,
             auction: next_account_info(accounts_iter)?,
             central_state: next_account_info(accounts_iter)?,
             state: next_account_info(accounts_iter)?,
@@ -61,7 +69,17 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
             destination_token: next_account_info(accounts_iter)?,
             bonfida_sol_vault: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
+            bid_accounts: None,
         };
+        if let Ok(spl_token_program) = next_account_info(accounts_iter) {
+            accounts.bid_accounts = Some(BidAccounts {
+                spl_token_program,
+                auction_pot: next_account_info(accounts_iter)?,
+                top_bid_refund: next_account_info(accounts_iter)?,
+                bonfida_vault: next_account_info(accounts_iter)?,
+                rent_sysvar: next_account_info(accounts_iter)?,
+            });
+        }
 
         // Params check and derivations
         let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + &name).as_bytes()])
@@ -82,8 +100,7 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
 
         let signer_seeds = name_account_key.to_bytes();
 
-        let (derived_state_key, derived_signer_nonce) =
-            Pubkey::find_program_address(&[&signer_seeds], program_id);
+        let (derived_state_key, _) = Pubkey::find_program_address(&[&signer_seeds], program_id);
 
         let reselling_state =
             ResellingAuction::unpack_unchecked(&accounts.reselling_state.data.borrow())?;
@@ -92,6 +109,8 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
             Pubkey::find_program_address(&[&name_account_key.to_bytes(), &[1u8, 1u8]], program_id);
 
         let destination_account = Account::unpack(&accounts.destination_token.data.borrow())?;
+        check_account_owner(accounts.central_state, program_id).unwrap();
+        let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
         let state = NameAuction::unpack_unchecked(&accounts.state.data.borrow()).unwrap();
 
         if state.status == NameAuctionStatus::FirstAuction {
@@ -99,22 +118,35 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
             return Err(ProgramError::InvalidArgument);
         }
 
-        let auction: AuctionData =
-            try_from_slice_unchecked(&accounts.auction.data.borrow()).unwrap();
-        let bids_empty = match auction.bid_state {
-            BidState::EnglishAuction { bids, max: _ } => bids.is_empty(),
-            _ => unreachable!(),
-        };
+        let auction = Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
 
-        if !bids_empty {
-            msg!("Cannot cancel auctions with bids");
-            return Err(ProgramError::InvalidArgument);
+        if auction.top_bid.is_some() {
+            if auction.settled {
+                msg!("The auction has already been claimed");
+                return Err(NameAuctionError::AuctionRealized.into());
+            }
+            let bid_accounts = accounts
+                .bid_accounts
+                .as_ref()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_account_key(bid_accounts.spl_token_program, &spl_token::id()).unwrap();
+            check_account_key(
+                bid_accounts.auction_pot,
+                &Auction::find_pot_key(program_id, &name_account_key).0,
+            )
+            .unwrap();
+            check_account_key(
+                bid_accounts.bonfida_vault,
+                &Pubkey::new(&central_state.config.bonfida_fida_vault),
+            )
+            .unwrap();
+            check_account_key(bid_accounts.rent_sysvar, &sysvar::rent::id()).unwrap();
         }
 
         // Key checks
         check_account_key(accounts.clock_sysvar, &sysvar::clock::id()).unwrap();
         check_account_key(accounts.naming_service_program, &spl_name_service::id()).unwrap();
-        check_account_key(accounts.auction_program, &AUCTION_PROGRAM_ID).unwrap();
+        check_account_key(accounts.system_program, &system_program::id()).unwrap();
         check_account_key(accounts.name, &name_account_key).unwrap();
         check_account_key(accounts.state, &derived_state_key).unwrap();
         check_account_key(accounts.reselling_state, &derived_reselling_state_key).unwrap();
@@ -124,19 +156,21 @@ impl<'a, 'b: 'a> Accounts<'a, 'b> {
         )
         .unwrap();
         check_account_key(accounts.auction_creator, &destination_account.owner).unwrap();
-        check_account_key(accounts.bonfida_sol_vault, &BONFIDA_SOL_VAULT).unwrap();
+        check_account_key(
+            accounts.bonfida_sol_vault,
+            &Pubkey::new(&central_state.config.bonfida_sol_vault),
+        )
+        .unwrap();
 
         // Signer checks
         check_signer(accounts.auction_creator).unwrap();
 
         // Ownership checks
-        check_account_owner(accounts.auction, &AUCTION_PROGRAM_ID).unwrap();
-        check_account_owner(accounts.central_state, program_id).unwrap();
         check_account_owner(accounts.state, program_id).unwrap();
         check_account_key(accounts.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
         check_account_owner(accounts.reselling_state, program_id).unwrap();
 
-        Ok((accounts, signer_seeds, derived_signer_nonce))
+        Ok((accounts, central_state, auction))
     }
 }
 
@@ -145,23 +179,69 @@ pub fn process_end_auction(
     accounts: &[AccountInfo],
     name: String,
 ) -> ProgramResult {
-    let (accounts, signer_seeds, derived_signer_nonce) =
-        Accounts::parse(program_id, accounts, name)?;
-
-    let signer_seeds: &[&[u8]] = &[&signer_seeds, &[derived_signer_nonce]];
+    let (accounts, central_state, mut auction) = Accounts::parse(program_id, accounts, name)?;
 
     msg!("Ending auction");
-    Cpi::end_auction(
-        accounts.auction_program,
-        accounts.state,
-        accounts.auction,
-        accounts.clock_sysvar,
-        *accounts.name.key,
-        signer_seeds,
-    )?;
+    let current_timestamp = Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp;
+    // Once bidding is over the top bidder has won, the auction has to be claimed
+    if auction.top_bid.is_some() && auction.bidding_ended(current_timestamp) {
+        msg!("Auctions with a bid can only be cancelled while bidding is open");
+        return Err(NameAuctionError::AuctionEnded.into());
+    }
+    if !auction.ended(current_timestamp) {
+        auction.ended_at = Some(current_timestamp);
+        auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+    }
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+
+    if let (Some(top_bid), Some(bid_accounts)) = (auction.top_bid.take(), &accounts.bid_accounts) {
+        // The seller pays a penalty, part of it compensates the top bidder along with the refund of
+        // the top bid
+        let penalty = ((top_bid.amount as u128)
+            * (central_state.config.cancellation_penalty_bps as u128)
+            / 10_000) as u64;
+        let compensation = ((penalty as u128)
+            * (central_state.config.cancellation_compensation_bps as u128)
+            / 10_000) as u64;
+        if compensation > 0 {
+            Cpi::transfer_tokens(
+                bid_accounts.spl_token_program,
+                accounts.destination_token,
+                bid_accounts.auction_pot,
+                accounts.auction_creator,
+                compensation,
+                None,
+            )?;
+        }
+        if penalty > compensation {
+            Cpi::transfer_tokens(
+                bid_accounts.spl_token_program,
+                accounts.destination_token,
+                bid_accounts.bonfida_vault,
+                accounts.auction_creator,
+                penalty - compensation,
+                None,
+            )?;
+        }
+        msg!("Refunding the top bid");
+        credit_refund(
+            program_id,
+            accounts.system_program,
+            bid_accounts.rent_sysvar,
+            accounts.auction_creator,
+            bid_accounts.top_bid_refund,
+            accounts.auction.key,
+            &Pubkey::new(&top_bid.bidder),
+            top_bid
+                .amount
+                .checked_add(compensation)
+                .ok_or(ProgramError::InvalidArgument)?,
+        )?;
+        auction.second_bid = None;
+        auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+    }
 
     msg!("Transferring domain names to auction creator");
     Cpi::transfer_name_account(
@@ -9,8 +9,8 @@ use solana_program::{
 };
 
 use crate::{
-    state::CentralState,
-    utils::{check_account_key, check_account_owner, Cpi},
+    state::{CentralState, ProgramConfig, CENTRAL_STATE_VERSION},
+    utils::{check_account_key, check_signer, Cpi},
 };
 
 struct Accounts<'a, 'b: 'a> {
@@ -32,9 +32,13 @@ fn parse_accounts<'a, 'b: 'a>(
         rent_sysvar_account: next_account_info(accounts_iter)?,
     };
 
-    check_account_owner(a.state_account, &system_program::id()).unwrap();
+    if a.state_account.owner != &system_program::id() || !a.state_account.data_is_empty() {
+        msg!("The central state is already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
     check_account_key(a.system_program, &system_program::id()).unwrap();
     check_account_key(a.rent_sysvar_account, &sysvar::rent::id()).unwrap();
+    check_signer(a.fee_payer).unwrap();
 
     Ok(a)
 }
@@ -43,32 +47,45 @@ pub fn process_init(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     state_nonce: u8,
+    admin: [u8; 32],
+    admin_claim_key: [u8; 32],
+    admin_create_key: [u8; 32],
+    config: ProgramConfig,
 ) -> ProgramResult {
-    // let accounts = parse_accounts(program_id, accounts)?;
+    let accounts = parse_accounts(program_id, accounts)?;
 
-    // let signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[state_nonce]];
-    // let (derived_state_key, state_nonce) =
-    //     Pubkey::find_program_address(&[&program_id.to_bytes()], program_id);
+    let (derived_state_key, derived_nonce) = CentralState::find_key(program_id);
 
-    // if &derived_state_key != accounts.state_account.key {
-    //     msg!("Incorrect state account or signer nonce provided");
-    //     return Err(ProgramError::InvalidArgument);
-    // }
+    if &derived_state_key != accounts.state_account.key || derived_nonce != state_nonce {
+        msg!("Incorrect state account or signer nonce provided");
+        return Err(ProgramError::InvalidArgument);
+    }
 
-    // Cpi::create_account(
-    //     program_id,
-    //     accounts.system_program,
-    //     accounts.fee_payer,
-    //     accounts.state_account,
-    //     accounts.rent_sysvar_account,
-    //     signer_seeds,
-    //     CentralState::LEN,
-    // )?;
+    config.check()?;
 
-    // let state = CentralState {
-    //     signer_nonce: state_nonce,
-    // };
-    // state.pack_into_slice(&mut accounts.state_account.data.borrow_mut());
+    let signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[state_nonce]];
+
+    Cpi::create_account(
+        program_id,
+        accounts.system_program,
+        accounts.fee_payer,
+        accounts.state_account,
+        accounts.rent_sysvar_account,
+        signer_seeds,
+        CentralState::LEN,
+    )?;
+
+    let state = CentralState {
+        signer_nonce: state_nonce,
+        version: CENTRAL_STATE_VERSION,
+        admin,
+        admin_claim_key,
+        admin_create_key,
+        config,
+        paused: 0,
+        pending_authorities: vec![],
+    };
+    state.pack_into_slice(&mut accounts.state_account.data.borrow_mut());
 
     Ok(())
 }
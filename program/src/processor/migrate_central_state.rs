@@ -0,0 +1,85 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{self, Sysvar},
+};
+
+use crate::{
+    migration::migrate,
+    state::CentralState,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    central_state: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        central_state: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+    };
+
+    check_account_key(a.central_state, &CentralState::find_key(program_id).0)?;
+    check_account_owner(a.central_state, program_id)?;
+    check_account_key(a.system_program, &system_program::id())?;
+    check_signer(a.fee_payer)?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+
+    Ok(a)
+}
+
+pub fn process_migrate_central_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let data = migrate(accounts.central_state.data.borrow().to_vec())?;
+    let len = CentralState::LEN.max(accounts.central_state.data_len());
+
+    let rent = Rent::from_account_info(accounts.rent_sysvar)?;
+    let missing_lamports = rent
+        .minimum_balance(len)
+        .saturating_sub(accounts.central_state.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(
+                accounts.fee_payer.key,
+                accounts.central_state.key,
+                missing_lamports,
+            ),
+            &[
+                accounts.system_program.clone(),
+                accounts.fee_payer.clone(),
+                accounts.central_state.clone(),
+            ],
+        )?;
+    }
+    if len > accounts.central_state.data_len() {
+        accounts.central_state.realloc(len, true)?;
+    }
+
+    let mut dst = accounts.central_state.data.borrow_mut();
+    dst.fill(0);
+    dst[..data.len()].copy_from_slice(&data);
+    msg!("Central state migrated to version {}", data[1]);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::{
+    auction::{credit_refund, Auction, Bid},
+    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    auction: &'a AccountInfo<'b>,
+    pot: &'a AccountInfo<'b>,
+    bidder: &'a AccountInfo<'b>,
+    bidder_token_source: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    outbid_refund: Option<&'a AccountInfo<'b>>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        spl_token_program: next_account_info(accounts_iter)?,
+        auction: next_account_info(accounts_iter)?,
+        pot: next_account_info(accounts_iter)?,
+        bidder: next_account_info(accounts_iter)?,
+        bidder_token_source: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        outbid_refund: next_account_info(accounts_iter).ok(),
+    };
+
+    // Check keys
+    check_account_key(a.spl_token_program, &spl_token::ID)?;
+    check_account_key(a.system_program, &system_program::ID)?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::ID)?;
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+
+    // Check signer
+    check_signer(a.bidder)?;
+
+    Ok(a)
+}
+
+pub fn process_place_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let name_account = Pubkey::new(&auction.resource);
+    check_account_key(
+        accounts.auction,
+        &Auction::find_key(program_id, &name_account).0,
+    )?;
+    check_account_key(
+        accounts.pot,
+        &Auction::find_pot_key(program_id, &name_account).0,
+    )?;
+
+    let bid = Bid {
+        bidder: accounts.bidder.key.to_bytes(),
+        amount,
+    };
+    let outbid = auction.place_bid(bid, Clock::get()?.unix_timestamp)?;
+    let amount = auction.top_bid.as_ref().map(|b| b.amount).unwrap_or(amount);
+
+    Cpi::transfer_tokens(
+        accounts.spl_token_program,
+        accounts.bidder_token_source,
+        accounts.pot,
+        accounts.bidder,
+        amount,
+        None,
+    )?;
+
+    // The previous bid stays in the pot until its bidder withdraws it
+    if let Some(outbid) = outbid {
+        msg!("Crediting the refund of the previous bid");
+        let outbid_refund = accounts
+            .outbid_refund
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        credit_refund(
+            program_id,
+            accounts.system_program,
+            accounts.rent_sysvar,
+            accounts.bidder,
+            outbid_refund,
+            accounts.auction.key,
+            &Pubkey::new(&outbid.bidder),
+            outbid.amount,
+        )?;
+    }
+
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
+    Ok(())
+}
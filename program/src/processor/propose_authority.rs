@@ -0,0 +1,68 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    state::{AdminAction, AuthorityRole, CentralState, PendingAuthority},
+    utils::{check_account_owner, execute_proposal},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    central_state: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        central_state: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+    };
+
+    // Check ownership
+    check_account_owner(a.central_state, program_id)?;
+
+    Ok(a)
+}
+
+pub fn process_propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+    new_key: [u8; 32],
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    execute_proposal(
+        program_id,
+        accounts.admin_set,
+        accounts.proposal,
+        &AdminAction::ProposeAuthority { role, new_key },
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let pending = PendingAuthority {
+        role,
+        new_key,
+        effective_at: now + central_state.config.authority_change_delay as i64,
+    };
+
+    // A new proposal for a role replaces the pending one
+    central_state.pending_authorities.retain(|p| p.role != role);
+    central_state.pending_authorities.push(pending);
+    central_state.pack_into_slice(&mut accounts.central_state.data.borrow_mut());
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::{
+    error::NameAuctionError,
+    state::{CentralState, Registration},
+    utils::{check_account_key, check_account_owner, Cpi},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+use spl_name_service::state::NameRecordHeader;
+
+use super::ROOT_DOMAIN_ACCOUNT;
+
+struct Accounts<'a, 'b: 'a> {
+    naming_service_program: &'a AccountInfo<'b>,
+    root_domain: &'a AccountInfo<'b>,
+    name: &'a AccountInfo<'b>,
+    name_class: &'a AccountInfo<'b>,
+    registration: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        naming_service_program: next_account_info(accounts_iter)?,
+        root_domain: next_account_info(accounts_iter)?,
+        name: next_account_info(accounts_iter)?,
+        name_class: next_account_info(accounts_iter)?,
+        registration: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.naming_service_program, &spl_name_service::ID)?;
+    check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT)?;
+    check_account_key(a.name_class, &system_program::ID)?;
+    check_account_key(
+        a.registration,
+        &Registration::find_key(program_id, a.name.key).0,
+    )?;
+
+    // Check ownership
+    check_account_owner(a.name, &spl_name_service::ID)?;
+    check_account_owner(a.registration, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+
+    Ok(a)
+}
+
+pub fn process_reclaim_expired(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+
+    let registration = Registration::unpack_from_slice(&accounts.registration.data.borrow())?;
+    if !registration.is_past_grace_period(Clock::get()?.unix_timestamp) {
+        msg!("The name expires at {}", registration.expires_at);
+        return Err(NameAuctionError::RegistrationNotExpired.into());
+    }
+
+    let name_record = NameRecordHeader::unpack_from_slice(&accounts.name.data.borrow())?;
+    if &name_record.owner == accounts.central_state.key {
+        msg!("The name is already held by the central state");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+
+    // The central state owns the root domain and takes the name back from its owner
+    Cpi::transfer_name_account_by_parent_owner(
+        accounts.naming_service_program,
+        accounts.central_state,
+        accounts.name,
+        accounts.name_class,
+        accounts.root_domain,
+        accounts.central_state.key,
+        central_state_signer_seeds,
+    )?;
+
+    Ok(())
+}
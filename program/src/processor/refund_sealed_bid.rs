@@ -0,0 +1,108 @@
+use crate::{
+    auction::{credit_refund, Auction, SealedBid},
+    error::NameAuctionError,
+    utils::{check_account_key, check_account_owner, check_signer},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    system_program: &'a AccountInfo<'b>,
+    auction: &'a AccountInfo<'b>,
+    sealed_bid: &'a AccountInfo<'b>,
+    bidder: &'a AccountInfo<'b>,
+    bid_refund: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        system_program: next_account_info(accounts_iter)?,
+        auction: next_account_info(accounts_iter)?,
+        sealed_bid: next_account_info(accounts_iter)?,
+        bidder: next_account_info(accounts_iter)?,
+        bid_refund: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.system_program, &system_program::ID)?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::ID)?;
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+
+    // Check signer
+    check_signer(a.fee_payer)?;
+
+    Ok(a)
+}
+
+// Anyone can return the deposits once the auction has ended, the winner keeps its bid in the pot
+pub fn process_refund_sealed_bid(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let name_account = Pubkey::new(&auction.resource);
+    check_account_key(
+        accounts.auction,
+        &Auction::find_key(program_id, &name_account).0,
+    )?;
+
+    let sealed_bid = SealedBid::from_account_info(
+        accounts.sealed_bid,
+        program_id,
+        accounts.auction.key,
+        accounts.bidder.key,
+    )?;
+
+    if !auction.ended(Clock::get()?.unix_timestamp) {
+        msg!("Deposits can only be refunded once the auction has ended");
+        return Err(NameAuctionError::AuctionInProgress.into());
+    }
+
+    let refund = match &auction.top_bid {
+        Some(bid) if sealed_bid.revealed && bid.bidder == sealed_bid.bidder => {
+            sealed_bid.deposit - bid.amount
+        }
+        _ => sealed_bid.deposit,
+    };
+    if refund > 0 {
+        credit_refund(
+            program_id,
+            accounts.system_program,
+            accounts.rent_sysvar,
+            accounts.fee_payer,
+            accounts.bid_refund,
+            accounts.auction.key,
+            accounts.bidder.key,
+            refund,
+        )?;
+    }
+
+    // Close the sealed bid account
+    let lamports = accounts.sealed_bid.lamports();
+    **accounts.sealed_bid.lamports.borrow_mut() = 0;
+    **accounts.bidder.lamports.borrow_mut() += lamports;
+    accounts.sealed_bid.data.borrow_mut().fill(0);
+
+    auction.open_commitments -= 1;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::{
+    error::NameAuctionError,
+    state::{CentralState, Registration, MAX_RENEWAL_YEARS, REGISTRATION_PERIOD},
+    utils::{
+        check_account_key, check_account_owner, check_signer, get_grapheme_len, get_quote_amount,
+        load_payment_mint, load_price_schedule,
+    },
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
+use spl_token::{instruction::transfer, state::Mint};
+
+use super::ROOT_DOMAIN_ACCOUNT;
+
+struct Accounts<'a, 'b: 'a> {
+    name: &'a AccountInfo<'b>,
+    registration: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+    buyer: &'a AccountInfo<'b>,
+    buyer_token_source: &'a AccountInfo<'b>,
+    vault: &'a AccountInfo<'b>,
+    spl_token_program: &'a AccountInfo<'b>,
+    price_schedule: &'a AccountInfo<'b>,
+    quote_mint: &'a AccountInfo<'b>,
+    payment_mint: &'a AccountInfo<'b>,
+    pyth_price_acc: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        name: next_account_info(accounts_iter)?,
+        registration: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+        buyer: next_account_info(accounts_iter)?,
+        buyer_token_source: next_account_info(accounts_iter)?,
+        vault: next_account_info(accounts_iter)?,
+        spl_token_program: next_account_info(accounts_iter)?,
+        price_schedule: next_account_info(accounts_iter)?,
+        quote_mint: next_account_info(accounts_iter)?,
+        payment_mint: next_account_info(accounts_iter)?,
+        pyth_price_acc: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.spl_token_program, &spl_token::ID)?;
+    check_account_key(
+        a.registration,
+        &Registration::find_key(program_id, a.name.key).0,
+    )?;
+
+    // Check ownership
+    check_account_owner(a.name, &spl_name_service::ID)?;
+    check_account_owner(a.registration, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+    check_account_owner(a.quote_mint, &spl_token::ID)?;
+
+    // Check signer
+    check_signer(a.buyer)?;
+
+    Ok(a)
+}
+
+pub fn process_renew(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    years: u8,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    if years == 0 || years > MAX_RENEWAL_YEARS {
+        msg!("Names can be renewed for 1 to {} years", MAX_RENEWAL_YEARS);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let payment_mint = load_payment_mint(
+        program_id,
+        accounts.payment_mint,
+        accounts.quote_mint.key,
+        &central_state,
+    )?;
+    check_account_key(accounts.vault, &Pubkey::new(&payment_mint.vault))?;
+
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + &name).as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (name_account_key, _) = get_seeds_and_key(
+        &spl_name_service::ID,
+        hashed_name,
+        None,
+        Some(&ROOT_DOMAIN_ACCOUNT),
+    );
+    if &name_account_key != accounts.name.key {
+        msg!("Provided wrong name account");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut registration = Registration::unpack_from_slice(&accounts.registration.data.borrow())?;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    if registration.is_past_grace_period(current_timestamp) {
+        msg!("The name expired at {}", registration.expires_at);
+        return Err(NameAuctionError::RegistrationExpired.into());
+    }
+    if registration.is_expired(current_timestamp) {
+        let name_record = NameRecordHeader::unpack_from_slice(&accounts.name.data.borrow())?;
+        if &name_record.owner != accounts.buyer.key {
+            msg!("Only the owner can renew a name during its grace period");
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+
+    let price_schedule = load_price_schedule(program_id, accounts.price_schedule)?;
+    let price_usd = price_schedule
+        .get_usd_price(get_grapheme_len(&name))
+        .checked_mul(years as u64)
+        .ok_or(ProgramError::InvalidArgument)?;
+
+    let mint_decimals = Mint::unpack(&accounts.quote_mint.data.borrow())?.decimals;
+    let price = get_quote_amount(
+        program_id,
+        price_usd,
+        &payment_mint,
+        mint_decimals,
+        accounts.pyth_price_acc,
+        &central_state.config,
+    )?;
+
+    // Transfer tokens
+    let transfer_ix = transfer(
+        &spl_token::ID,
+        accounts.buyer_token_source.key,
+        accounts.vault.key,
+        accounts.buyer.key,
+        &[],
+        price,
+    )?;
+
+    invoke(
+        &transfer_ix,
+        &[
+            accounts.spl_token_program.clone(),
+            accounts.buyer_token_source.clone(),
+            accounts.vault.clone(),
+            accounts.buyer.clone(),
+        ],
+    )?;
+
+    // Renewals extend the current registration, even during the grace period
+    registration.expires_at += years as i64 * REGISTRATION_PERIOD;
+    registration.pack_into_slice(&mut accounts.registration.data.borrow_mut());
+
+    Ok(())
+}
@@ -1,7 +1,6 @@
 use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
     clock::Clock,
     entrypoint::ProgramResult,
     hash::hashv,
@@ -12,18 +11,18 @@ use solana_program::{
     system_program,
     sysvar::{self, Sysvar},
 };
-use spl_auction::processor::{AuctionData, BidState};
 use spl_name_service::state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX};
 
 use crate::{
+    auction::{check_auction_reusable, create_auction_accounts, Auction},
     error::NameAuctionError,
     processor::TOKEN_MINT,
-    state::{NameAuction, NameAuctionStatus, ResellingAuction},
+    state::{CentralState, NameAuction, NameAuctionStatus, ResellingAuction},
     utils::{check_account_key, check_account_owner, check_signer, Cpi},
 };
 use spl_token::state::Account;
 
-use super::{AUCTION_PROGRAM_ID, ROOT_DOMAIN_ACCOUNT};
+use super::ROOT_DOMAIN_ACCOUNT;
 
 struct Accounts<'a, 'b: 'a> {
     rent_sysvar: &'a AccountInfo<'b>,
@@ -38,10 +37,11 @@ struct Accounts<'a, 'b: 'a> {
     central_state: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
     reselling_state: &'a AccountInfo<'b>,
-    auction_program: &'a AccountInfo<'b>,
+    spl_token_program: &'a AccountInfo<'b>,
     token_destination_account: &'a AccountInfo<'b>,
     fee_payer: &'a AccountInfo<'b>,
-    buy_now: Option<&'a AccountInfo<'b>>,
+    quote_mint: &'a AccountInfo<'b>,
+    auction_pot: &'a AccountInfo<'b>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
@@ -58,14 +58,15 @@ fn parse_accounts<'a, 'b: 'a>(
         name_owner: next_account_info(accounts_iter)?,
         reverse_lookup: next_account_info(accounts_iter)?,
         system_program: next_account_info(accounts_iter)?,
-        auction_program: next_account_info(accounts_iter)?,
+        spl_token_program: next_account_info(accounts_iter)?,
         auction: next_account_info(accounts_iter)?,
         central_state: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
         reselling_state: next_account_info(accounts_iter)?,
         token_destination_account: next_account_info(accounts_iter)?,
         fee_payer: next_account_info(accounts_iter)?,
-        buy_now: next_account_info(accounts_iter).ok(),
+        quote_mint: next_account_info(accounts_iter)?,
+        auction_pot: next_account_info(accounts_iter)?,
     };
 
     check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
@@ -75,8 +76,8 @@ fn parse_accounts<'a, 'b: 'a>(
     check_account_key(a.root_domain, &ROOT_DOMAIN_ACCOUNT).unwrap();
     check_account_key(a.system_program, &system_program::id()).unwrap();
     check_account_owner(a.central_state, program_id).unwrap();
-    check_account_key(a.auction_program, &AUCTION_PROGRAM_ID).unwrap();
-    // check_account_owner(a.auction, &spl_auction::id()).unwrap();
+    check_account_key(a.spl_token_program, &spl_token::ID).unwrap();
+    check_account_key(a.quote_mint, &TOKEN_MINT).unwrap();
     check_account_owner(a.state, &system_program::id())
         .or_else(|_| check_account_owner(a.state, program_id))
         .unwrap();
@@ -88,6 +89,7 @@ fn parse_accounts<'a, 'b: 'a>(
     Ok(a)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_resell(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -95,6 +97,10 @@ pub fn process_resell(
     minimum_price: u64,
     en_auction_at: u64,
     max_price: Option<u64>,
+    end_auction_gap: Option<u64>,
+    bid_increment_bps: Option<u64>,
+    second_price: bool,
+    reserve_commitment: Option<[u8; 32]>,
 ) -> ProgramResult {
     let accounts = parse_accounts(program_id, accounts)?;
 
@@ -123,6 +129,10 @@ pub fn process_resell(
         msg!("Name account is not initialized. Please create an auction before reselling.");
         return Err(ProgramError::UninitializedAccount);
     }
+    if matches!(max_price, Some(p) if p < minimum_price) {
+        msg!("The buy now price cannot be lower than the minimum price");
+        return Err(ProgramError::InvalidArgument);
+    }
     let token_destination_account =
         Account::unpack(&accounts.token_destination_account.data.borrow())?;
     if TOKEN_MINT != token_destination_account.mint {
@@ -164,10 +174,10 @@ pub fn process_resell(
 
         if name_record.owner == *accounts.central_state.key {
             let current_timestamp = Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp;
-            let auction: AuctionData =
-                try_from_slice_unchecked(&accounts.auction.data.borrow()).unwrap();
+            let mut auction =
+                Auction::from_account_info(accounts.auction, program_id, &name_account_key)?;
 
-            if !auction.ended(current_timestamp)? {
+            if !auction.ended(current_timestamp) {
                 msg!("The auction has to end before it can be restarted!");
                 return Err(NameAuctionError::AuctionInProgress.into());
             }
@@ -178,22 +188,14 @@ pub fn process_resell(
                     return Err(ProgramError::InvalidArgument);
                 }
                 NameAuctionStatus::SecondaryAuction => {
-                    if let BidState::EnglishAuction { bids, max: _ } = auction.bid_state {
-                        if !bids.is_empty() {
-                            msg!("The auction has a bidder, which means it has a winner and cannot be reset!");
-                            return Err(NameAuctionError::AuctionRealized.into());
-                        }
-                        msg!("Restarting auction.");
-                        Cpi::start_auction(
-                            accounts.auction_program,
-                            accounts.clock_sysvar,
-                            accounts.auction,
-                            accounts.state,
-                            *accounts.name.key,
-                            state_signer_seeds,
-                        )?;
-                        return Ok(());
+                    if auction.top_bid.is_some() {
+                        msg!("The auction has a bidder, which means it has a winner and cannot be reset!");
+                        return Err(NameAuctionError::AuctionRealized.into());
                     }
+                    msg!("Restarting auction.");
+                    auction.start(current_timestamp)?;
+                    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+                    return Ok(());
                 }
                 _ => {
                     unreachable!()
@@ -219,9 +221,22 @@ pub fn process_resell(
         return Err(ProgramError::InvalidArgument);
     }
 
-    let central_state_nonce = accounts.central_state.data.borrow()[0];
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let end_auction_gap = end_auction_gap.unwrap_or(central_state.config.end_auction_gap);
+    let bid_increment_bps = bid_increment_bps.unwrap_or(central_state.config.bid_increment_bps);
+    central_state
+        .config
+        .check_auction_params(end_auction_gap, bid_increment_bps)?;
+    if en_auction_at > central_state.config.auction_max_length {
+        msg!(
+            "The auction cannot last longer than {} seconds",
+            central_state.config.auction_max_length
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
 
-    let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
 
     if accounts.state.data_len() == 0 {
         Cpi::create_account(
@@ -270,19 +285,19 @@ pub fn process_resell(
     msg!("Setting up auction");
     solana_program::log::sol_log_compute_units();
 
-    Cpi::create_auction(
-        accounts.auction_program,
-        accounts.rent_sysvar,
+    let current_timestamp = Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp;
+    check_auction_reusable(accounts.auction, current_timestamp)?;
+    create_auction_accounts(
+        program_id,
         accounts.system_program,
-        accounts.auction,
+        accounts.spl_token_program,
+        accounts.rent_sysvar,
         accounts.fee_payer,
-        Some(en_auction_at),
-        accounts.state,
-        accounts.buy_now,
-        *accounts.name.key,
-        minimum_price,
-        state_signer_seeds,
-        max_price,
+        accounts.auction,
+        accounts.auction_pot,
+        accounts.quote_mint,
+        accounts.central_state,
+        &name_account_key,
     )?;
 
     msg!("Transferring the domain ownership to the auction program");
@@ -299,14 +314,23 @@ pub fn process_resell(
 
     msg!("Starting auction");
 
-    Cpi::start_auction(
-        accounts.auction_program,
-        accounts.clock_sysvar,
-        accounts.auction,
-        accounts.state,
-        *accounts.name.key,
-        state_signer_seeds,
-    )?;
+    let mut auction = Auction::new(
+        &name_account_key,
+        &TOKEN_MINT,
+        minimum_price,
+        max_price,
+        en_auction_at as i64,
+        end_auction_gap as i64,
+        bid_increment_bps,
+    );
+    if second_price {
+        auction = auction.with_second_price();
+    }
+    if let Some(reserve_commitment) = reserve_commitment {
+        auction = auction.with_hidden_reserve(reserve_commitment);
+    }
+    auction.start(current_timestamp)?;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
 
     if accounts.reverse_lookup.data_len() == 0 {
         Cpi::create_reverse_lookup_account(
@@ -1,27 +1,30 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    system_program, sysvar,
+    system_program,
+    sysvar::{self, Sysvar},
 };
 
 use crate::{
-    state::{NameAuction, NameAuctionStatus},
-    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+    auction::Auction,
+    error::NameAuctionError,
+    state::{AdminAction, CentralState, NameAuction, NameAuctionStatus},
+    utils::{check_account_key, check_account_owner, execute_proposal},
 };
 
-use super::{ADMIN, AUCTION_PROGRAM_ID};
-
 struct Accounts<'a, 'b: 'a> {
-    auction_program: &'a AccountInfo<'b>,
     clock_sysvar: &'a AccountInfo<'b>,
-    admin: &'a AccountInfo<'b>,
     auction: &'a AccountInfo<'b>,
     name: &'a AccountInfo<'b>,
     state: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
@@ -30,23 +33,33 @@ fn parse_accounts<'a, 'b: 'a>(
 ) -> Result<Accounts<'a, 'b>, ProgramError> {
     let accounts_iter = &mut accounts.iter();
     let a = Accounts {
-        auction_program: next_account_info(accounts_iter)?,
         clock_sysvar: next_account_info(accounts_iter)?,
-        admin: next_account_info(accounts_iter)?,
         auction: next_account_info(accounts_iter)?,
         name: next_account_info(accounts_iter)?,
         state: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
     };
 
-    check_account_key(a.auction_program, &AUCTION_PROGRAM_ID).unwrap();
     check_account_key(a.clock_sysvar, &sysvar::clock::id()).unwrap();
-    check_account_owner(a.auction, &AUCTION_PROGRAM_ID).unwrap();
+    check_account_owner(a.auction, program_id).unwrap();
     check_account_owner(a.state, program_id).unwrap();
     check_account_owner(a.name, &system_program::id()).unwrap();
+    check_account_owner(a.central_state, program_id).unwrap();
 
     #[cfg(not(feature = "no-admin"))]
-    check_account_key(a.admin, &ADMIN).unwrap();
-    check_signer(a.admin).unwrap();
+    {
+        CentralState::from_account_info(a.central_state, program_id)?;
+        execute_proposal(
+            program_id,
+            a.admin_set,
+            a.proposal,
+            &AdminAction::ResetAuction {
+                name: a.name.key.to_bytes(),
+            },
+        )?;
+    }
 
     Ok(a)
 }
@@ -54,10 +67,8 @@ fn parse_accounts<'a, 'b: 'a>(
 pub fn process_reset_auction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let accounts = parse_accounts(program_id, accounts)?;
 
-    let signer_seeds = accounts.name.key.to_bytes();
-
-    let (derived_state_key, derived_signer_nonce) =
-        Pubkey::find_program_address(&[&signer_seeds], program_id);
+    let (derived_state_key, _) =
+        Pubkey::find_program_address(&[&accounts.name.key.to_bytes()], program_id);
 
     if &derived_state_key != accounts.state.key {
         msg!("An invalid signer account was provided");
@@ -70,15 +81,12 @@ pub fn process_reset_auction(program_id: &Pubkey, accounts: &[AccountInfo]) -> P
         return Err(ProgramError::InvalidArgument);
     };
 
-    let signer_seeds: &[&[u8]] = &[&signer_seeds, &[derived_signer_nonce]];
-
-    Cpi::start_auction(
-        accounts.auction_program,
-        accounts.clock_sysvar,
-        accounts.auction,
-        accounts.state,
-        *accounts.name.key,
-        signer_seeds,
-    )?;
+    let mut auction = Auction::from_account_info(accounts.auction, program_id, accounts.name.key)?;
+    if auction.top_bid.is_some() {
+        msg!("The auction has a bidder and cannot be reset");
+        return Err(NameAuctionError::AuctionRealized.into());
+    }
+    auction.start(Clock::from_account_info(accounts.clock_sysvar)?.unix_timestamp)?;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
     Ok(())
 }
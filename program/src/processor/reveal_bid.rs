@@ -0,0 +1,82 @@
+use crate::{
+    auction::{Auction, Bid, SealedBid},
+    utils::{check_account_owner, check_signer},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+struct Accounts<'a, 'b: 'a> {
+    auction: &'a AccountInfo<'b>,
+    sealed_bid: &'a AccountInfo<'b>,
+    bidder: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        auction: next_account_info(accounts_iter)?,
+        sealed_bid: next_account_info(accounts_iter)?,
+        bidder: next_account_info(accounts_iter)?,
+    };
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+
+    // Check signer
+    check_signer(a.bidder)?;
+
+    Ok(a)
+}
+
+pub fn process_reveal_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    salt: [u8; 32],
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let mut sealed_bid = SealedBid::from_account_info(
+        accounts.sealed_bid,
+        program_id,
+        accounts.auction.key,
+        accounts.bidder.key,
+    )?;
+
+    if sealed_bid.revealed {
+        msg!("The bid has already been revealed");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if SealedBid::commitment(accounts.bidder.key, amount, &salt) != sealed_bid.commitment {
+        msg!("The revealed bid does not match the commitment");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if amount > sealed_bid.deposit {
+        msg!("The revealed bid is not covered by the deposit");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let bid = Bid {
+        bidder: sealed_bid.bidder,
+        amount,
+    };
+    auction.reveal_bid(bid, Clock::get()?.unix_timestamp)?;
+    sealed_bid.revealed = true;
+
+    sealed_bid.pack_into_slice(&mut accounts.sealed_bid.data.borrow_mut());
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    auction::Auction,
+    state::CentralState,
+    utils::{check_account_key, check_account_owner, check_reselling_seller},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    auction: &'a AccountInfo<'b>,
+    reselling_state: &'a AccountInfo<'b>,
+    destination_token: &'a AccountInfo<'b>,
+    seller: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        auction: next_account_info(accounts_iter)?,
+        reselling_state: next_account_info(accounts_iter)?,
+        destination_token: next_account_info(accounts_iter)?,
+        seller: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+    };
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+
+    Ok(a)
+}
+
+pub fn process_reveal_reserve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reserve: u64,
+    salt: [u8; 32],
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let mut auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let name_account = Pubkey::new(&auction.resource);
+    check_account_key(
+        accounts.auction,
+        &Auction::find_key(program_id, &name_account).0,
+    )?;
+    check_reselling_seller(
+        program_id,
+        &name_account,
+        accounts.reselling_state,
+        accounts.destination_token,
+        accounts.seller,
+    )?;
+
+    auction.reveal_reserve(
+        reserve,
+        &salt,
+        Clock::get()?.unix_timestamp,
+        central_state.config.reveal_length as i64,
+    )?;
+    auction.pack_into_slice(&mut accounts.auction.data.borrow_mut());
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{
+    state::{AdminAction, AdminSet, CentralState},
+    utils::{check_account_key, check_signer, execute_proposal, Cpi},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    admin: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    proposal: Option<&'a AccountInfo<'b>>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        admin: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter).ok(),
+    };
+
+    // Check keys
+    check_account_key(a.admin_set, &AdminSet::find_key(program_id).0)?;
+    check_account_key(a.system_program, &system_program::id())?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+
+    let central_state = CentralState::from_account_info(a.central_state, program_id)?;
+    check_account_key(a.admin, &Pubkey::new(&central_state.admin))?;
+
+    // Check signer
+    check_signer(a.admin)?;
+    check_signer(a.fee_payer)?;
+
+    Ok(a)
+}
+
+pub fn process_set_admin_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    admins: Vec<[u8; 32]>,
+    threshold: u8,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    AdminSet::check(&admins, threshold)?;
+
+    let proposal_count = if accounts.admin_set.data_is_empty() {
+        // The first admin set is registered by the central state admin alone
+        let (_, nonce) = AdminSet::find_key(program_id);
+        Cpi::create_account(
+            program_id,
+            accounts.system_program,
+            accounts.fee_payer,
+            accounts.admin_set,
+            accounts.rent_sysvar,
+            &[AdminSet::SEED, &[nonce]],
+            AdminSet::LEN,
+        )?;
+        0
+    } else {
+        // Subsequent changes have to be approved by the current admin set
+        let proposal = accounts.proposal.ok_or_else(|| {
+            msg!("Changing the admin set requires an approved proposal");
+            ProgramError::NotEnoughAccountKeys
+        })?;
+        execute_proposal(
+            program_id,
+            accounts.admin_set,
+            proposal,
+            &AdminAction::SetAdminSet {
+                admins: admins.clone(),
+                threshold,
+            },
+        )?;
+        AdminSet::unpack_unchecked(&accounts.admin_set.data.borrow())?.proposal_count
+    };
+
+    let admin_set = AdminSet {
+        threshold,
+        proposal_count,
+        admins,
+    };
+    admin_set.pack_into_slice(&mut accounts.admin_set.data.borrow_mut());
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    state::{AdminAction, CentralState},
+    utils::{check_account_owner, execute_proposal},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    central_state: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        central_state: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+    };
+
+    // Check ownership
+    check_account_owner(a.central_state, program_id)?;
+
+    Ok(a)
+}
+
+pub fn process_set_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    flags: u64,
+    paused: bool,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let mut central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    execute_proposal(
+        program_id,
+        accounts.admin_set,
+        accounts.proposal,
+        &AdminAction::SetPause { flags, paused },
+    )?;
+
+    if paused {
+        central_state.paused |= flags;
+    } else {
+        central_state.paused &= !flags;
+    }
+    msg!("Pause flags: {:#x}", central_state.paused);
+
+    central_state.pack_into_slice(&mut accounts.central_state.data.borrow_mut());
+
+    Ok(())
+}
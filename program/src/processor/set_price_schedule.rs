@@ -0,0 +1,89 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{
+    state::{AdminAction, CentralState, PriceSchedule},
+    utils::{check_account_key, check_account_owner, check_signer, execute_proposal, Cpi},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    central_state: &'a AccountInfo<'b>,
+    price_schedule: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        central_state: next_account_info(accounts_iter)?,
+        price_schedule: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.price_schedule, &PriceSchedule::find_key(program_id).0)?;
+    check_account_key(a.system_program, &system_program::id())?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+
+    // Check ownership
+    check_account_owner(a.central_state, program_id)?;
+    check_account_owner(a.price_schedule, &system_program::id())
+        .or_else(|_| check_account_owner(a.price_schedule, program_id))?;
+
+    // Check signer
+    check_signer(a.fee_payer)?;
+
+    Ok(a)
+}
+
+pub fn process_set_price_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    schedule: PriceSchedule,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    CentralState::from_account_info(accounts.central_state, program_id)?;
+    execute_proposal(
+        program_id,
+        accounts.admin_set,
+        accounts.proposal,
+        &AdminAction::SetPriceSchedule(schedule.clone()),
+    )?;
+
+    schedule.check()?;
+
+    if accounts.price_schedule.data_is_empty() {
+        let (_, nonce) = PriceSchedule::find_key(program_id);
+        Cpi::create_account(
+            program_id,
+            accounts.system_program,
+            accounts.fee_payer,
+            accounts.price_schedule,
+            accounts.rent_sysvar,
+            &[PriceSchedule::SEED, &[nonce]],
+            PriceSchedule::LEN,
+        )?;
+    }
+
+    schedule.pack_into_slice(&mut accounts.price_schedule.data.borrow_mut());
+
+    Ok(())
+}
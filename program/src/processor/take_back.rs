@@ -1,114 +1,146 @@
-use crate::processor::{CENTRAL_STATE, ROOT_DOMAIN_ACCOUNT};
-use crate::utils::{check_account_key, check_account_owner, check_signer};
-use borsh::BorshSerialize;
+use crate::processor::ROOT_DOMAIN_ACCOUNT;
+use crate::state::{AdminAction, CentralState, TakeBackReason, TakeBackRecord};
+use crate::utils::{check_account_key, check_account_owner, check_signer, execute_proposal, Cpi};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
-    instruction::{AccountMeta, Instruction},
-    program::invoke_signed,
+    msg,
     program_error::ProgramError,
-    pubkey,
+    program_pack::Pack,
     pubkey::Pubkey,
+    system_program,
+    sysvar::{self, Sysvar},
 };
-use spl_name_service::instruction::NameRegistryInstruction;
-
-fn transfer(
-    name_service_program_id: Pubkey,
-    new_owner: Pubkey,
-    name_account_key: Pubkey,
-    name_owner_key: Pubkey,
-    name_class_opt: Option<Pubkey>,
-    name_parent: Option<Pubkey>,
-) -> Result<Instruction, ProgramError> {
-    let instruction_data = NameRegistryInstruction::Transfer { new_owner };
-    let data = instruction_data.try_to_vec().unwrap();
-    let mut accounts = vec![
-        AccountMeta::new(name_account_key, false),
-        AccountMeta::new_readonly(name_owner_key, true),
-    ];
-
-    if let Some(key) = name_class_opt {
-        accounts.push(AccountMeta::new_readonly(key, false));
-    }
-
-    if let Some(key) = name_parent {
-        accounts.push(AccountMeta::new_readonly(key, false));
-    }
-
-    Ok(Instruction {
-        program_id: name_service_program_id,
-        accounts,
-        data,
-    })
-}
-
-const ADMIN: Pubkey = pubkey!("VBx642K1hYGLU5Zm1CHW1uRXAtFgxN5mRqyMcXnLZFW");
+use spl_name_service::state::NameRecordHeader;
 
 struct Accounts<'a, 'b: 'a> {
-    admin: &'a AccountInfo<'b>,
     naming_service_program: &'a AccountInfo<'b>,
     name: &'a AccountInfo<'b>,
     name_owner: &'a AccountInfo<'b>,
     name_class: &'a AccountInfo<'b>,
     parent_name: &'a AccountInfo<'b>,
     new_owner: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+    take_back_record: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
 }
 
 fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
     accounts: &'a [AccountInfo<'b>],
+    reason: TakeBackReason,
 ) -> Result<Accounts<'a, 'b>, ProgramError> {
     let accounts_iter = &mut accounts.iter();
     let a = Accounts {
-        admin: next_account_info(accounts_iter)?,
         naming_service_program: next_account_info(accounts_iter)?,
         name: next_account_info(accounts_iter)?,
         name_owner: next_account_info(accounts_iter)?,
         name_class: next_account_info(accounts_iter)?,
         parent_name: next_account_info(accounts_iter)?,
         new_owner: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+        take_back_record: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
     };
 
     // Check keys
-    check_account_key(a.admin, &ADMIN).unwrap();
     check_account_key(a.naming_service_program, &spl_name_service::id()).unwrap();
-    check_account_key(a.name_owner, &CENTRAL_STATE).unwrap();
+    check_account_key(a.parent_name, &ROOT_DOMAIN_ACCOUNT).unwrap();
+    check_account_key(a.system_program, &system_program::id()).unwrap();
+    check_account_key(a.rent_sysvar, &sysvar::rent::id()).unwrap();
+
+    CentralState::from_account_info(a.name_owner, program_id)?;
+    execute_proposal(
+        program_id,
+        a.admin_set,
+        a.proposal,
+        &AdminAction::TakeBack {
+            name: a.name.key.to_bytes(),
+            new_owner: a.new_owner.key.to_bytes(),
+            reason,
+        },
+    )?;
 
     // Check ownership
     check_account_owner(a.name, &spl_name_service::ID).unwrap();
+    check_account_owner(a.take_back_record, &system_program::id())
+        .or_else(|_| check_account_owner(a.take_back_record, program_id))
+        .unwrap();
 
     // Check signer
-    check_signer(a.admin).unwrap();
+    check_signer(a.fee_payer).unwrap();
 
     Ok(a)
 }
 
-pub fn process_take_back(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    // let accounts = parse_accounts(accounts)?;
-    // let central_state_nonce = accounts.name_owner.data.borrow()[0];
-
-    // let central_state_signer_seeds: &[&[u8]] = &[&program_id.to_bytes(), &[central_state_nonce]];
-
-    // let ix = transfer(
-    //     spl_name_service::ID,
-    //     *accounts.new_owner.key,
-    //     *accounts.name.key,
-    //     *accounts.name_owner.key,
-    //     Some(Pubkey::default()),
-    //     Some(ROOT_DOMAIN_ACCOUNT),
-    // )
-    // .unwrap();
-
-    // invoke_signed(
-    //     &ix,
-    //     &[
-    //         accounts.naming_service_program.clone(),
-    //         accounts.name.clone(),
-    //         accounts.name_owner.clone(),
-    //         accounts.name_class.clone(),
-    //         accounts.parent_name.clone(),
-    //     ],
-    //     &[central_state_signer_seeds],
-    // )?;
+pub fn process_take_back(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reason: TakeBackReason,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts, reason)?;
+    let central_state = CentralState::from_account_info(accounts.name_owner, program_id)?;
+
+    // Only names held by the central state can be taken back
+    let name_record = NameRecordHeader::unpack_from_slice(&accounts.name.data.borrow())?;
+    if &name_record.owner != accounts.name_owner.key {
+        msg!("The name is not held by the central state");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+
+    Cpi::transfer_name_account_by_parent_owner(
+        accounts.naming_service_program,
+        accounts.name_owner,
+        accounts.name,
+        accounts.name_class,
+        accounts.parent_name,
+        accounts.new_owner.key,
+        central_state_signer_seeds,
+    )?;
+
+    let (record_key, record_nonce) = TakeBackRecord::find_key(program_id, accounts.name.key);
+    check_account_key(accounts.take_back_record, &record_key)?;
+
+    let count = if accounts.take_back_record.data_is_empty() {
+        Cpi::create_account(
+            program_id,
+            accounts.system_program,
+            accounts.fee_payer,
+            accounts.take_back_record,
+            accounts.rent_sysvar,
+            &[
+                TakeBackRecord::SEED,
+                &accounts.name.key.to_bytes(),
+                &[record_nonce],
+            ],
+            TakeBackRecord::LEN,
+        )?;
+        1
+    } else {
+        TakeBackRecord::unpack_from_slice(&accounts.take_back_record.data.borrow())?.count + 1
+    };
+
+    msg!("Taking back name for reason {:?}", reason);
+
+    let record = TakeBackRecord {
+        name_account: accounts.name.key.to_bytes(),
+        new_owner: accounts.new_owner.key.to_bytes(),
+        proposal: accounts.proposal.key.to_bytes(),
+        reason,
+        taken_back_at: Clock::get()?.unix_timestamp,
+        count,
+    };
+    record.pack_into_slice(&mut accounts.take_back_record.data.borrow_mut());
 
     Ok(())
 }
@@ -0,0 +1,121 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{
+    state::{AdminAction, BlocklistPage, CentralState, MAX_BLOCKLIST_PAGE_LEN},
+    utils::{check_account_key, check_account_owner, check_signer, execute_proposal, Cpi},
+};
+
+struct Accounts<'a, 'b: 'a> {
+    central_state: &'a AccountInfo<'b>,
+    blocklist_page: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    fee_payer: &'a AccountInfo<'b>,
+    rent_sysvar: &'a AccountInfo<'b>,
+    admin_set: &'a AccountInfo<'b>,
+    proposal: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        central_state: next_account_info(accounts_iter)?,
+        blocklist_page: next_account_info(accounts_iter)?,
+        system_program: next_account_info(accounts_iter)?,
+        fee_payer: next_account_info(accounts_iter)?,
+        rent_sysvar: next_account_info(accounts_iter)?,
+        admin_set: next_account_info(accounts_iter)?,
+        proposal: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.system_program, &system_program::id())?;
+    check_account_key(a.rent_sysvar, &sysvar::rent::id())?;
+
+    // Check ownership
+    check_account_owner(a.central_state, program_id)?;
+    check_account_owner(a.blocklist_page, &system_program::id())
+        .or_else(|_| check_account_owner(a.blocklist_page, program_id))?;
+
+    // Check signer
+    check_signer(a.fee_payer)?;
+
+    Ok(a)
+}
+
+pub fn process_update_blocklist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    page: u8,
+    add: Vec<[u8; 32]>,
+    remove: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    CentralState::from_account_info(accounts.central_state, program_id)?;
+    execute_proposal(
+        program_id,
+        accounts.admin_set,
+        accounts.proposal,
+        &AdminAction::UpdateBlocklist {
+            page,
+            add: add.clone(),
+            remove: remove.clone(),
+        },
+    )?;
+
+    let (blocklist_page_key, blocklist_page_nonce) = BlocklistPage::find_key(program_id, page);
+    check_account_key(accounts.blocklist_page, &blocklist_page_key)?;
+
+    let mut blocklist_page = if accounts.blocklist_page.data_is_empty() {
+        Cpi::create_account(
+            program_id,
+            accounts.system_program,
+            accounts.fee_payer,
+            accounts.blocklist_page,
+            accounts.rent_sysvar,
+            &[BlocklistPage::SEED, &[page], &[blocklist_page_nonce]],
+            BlocklistPage::LEN,
+        )?;
+        BlocklistPage {
+            page,
+            hashed_names: vec![],
+        }
+    } else {
+        BlocklistPage::unpack_from_slice(&accounts.blocklist_page.data.borrow())?
+    };
+
+    blocklist_page.hashed_names.retain(|h| !remove.contains(h));
+
+    for hashed_name in add {
+        if BlocklistPage::page_of(&hashed_name) != page {
+            msg!("The hashed name does not belong to this blocklist page");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if !blocklist_page.hashed_names.contains(&hashed_name) {
+            blocklist_page.hashed_names.push(hashed_name);
+        }
+    }
+
+    if blocklist_page.hashed_names.len() > MAX_BLOCKLIST_PAGE_LEN {
+        msg!(
+            "A blocklist page cannot contain more than {} names",
+            MAX_BLOCKLIST_PAGE_LEN
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    blocklist_page.pack_into_slice(&mut accounts.blocklist_page.data.borrow_mut());
+
+    Ok(())
+}
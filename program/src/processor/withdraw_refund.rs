@@ -0,0 +1,92 @@
+use crate::{
+    auction::{Auction, BidRefund},
+    state::CentralState,
+    utils::{check_account_key, check_account_owner, check_signer, Cpi},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+struct Accounts<'a, 'b: 'a> {
+    spl_token_program: &'a AccountInfo<'b>,
+    auction: &'a AccountInfo<'b>,
+    pot: &'a AccountInfo<'b>,
+    bid_refund: &'a AccountInfo<'b>,
+    bidder: &'a AccountInfo<'b>,
+    destination_token: &'a AccountInfo<'b>,
+    central_state: &'a AccountInfo<'b>,
+}
+
+fn parse_accounts<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<Accounts<'a, 'b>, ProgramError> {
+    let accounts_iter = &mut accounts.iter();
+    let a = Accounts {
+        spl_token_program: next_account_info(accounts_iter)?,
+        auction: next_account_info(accounts_iter)?,
+        pot: next_account_info(accounts_iter)?,
+        bid_refund: next_account_info(accounts_iter)?,
+        bidder: next_account_info(accounts_iter)?,
+        destination_token: next_account_info(accounts_iter)?,
+        central_state: next_account_info(accounts_iter)?,
+    };
+
+    // Check keys
+    check_account_key(a.spl_token_program, &spl_token::ID)?;
+
+    // Check ownership
+    check_account_owner(a.auction, program_id)?;
+    check_account_owner(a.central_state, program_id)?;
+
+    // Check signer
+    check_signer(a.bidder)?;
+
+    Ok(a)
+}
+
+pub fn process_withdraw_refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = parse_accounts(program_id, accounts)?;
+
+    let central_state = CentralState::from_account_info(accounts.central_state, program_id)?;
+    let auction = Auction::unpack_from_slice(&accounts.auction.data.borrow())?;
+    let name_account = Pubkey::new(&auction.resource);
+    check_account_key(
+        accounts.auction,
+        &Auction::find_key(program_id, &name_account).0,
+    )?;
+    check_account_key(
+        accounts.pot,
+        &Auction::find_pot_key(program_id, &name_account).0,
+    )?;
+
+    let bid_refund = BidRefund::from_account_info(
+        accounts.bid_refund,
+        program_id,
+        accounts.auction.key,
+        accounts.bidder.key,
+    )?;
+
+    let central_state_signer_seeds: &[&[u8]] =
+        &[&program_id.to_bytes(), &[central_state.signer_nonce]];
+    Cpi::transfer_tokens(
+        accounts.spl_token_program,
+        accounts.pot,
+        accounts.destination_token,
+        accounts.central_state,
+        bid_refund.amount,
+        Some(central_state_signer_seeds),
+    )?;
+
+    // Close the refund account, it is created again if the bidder is owed more
+    let lamports = accounts.bid_refund.lamports();
+    **accounts.bid_refund.lamports.borrow_mut() = 0;
+    **accounts.bidder.lamports.borrow_mut() += lamports;
+    accounts.bid_refund.data.borrow_mut().fill(0);
+
+    Ok(())
+}
@@ -1,7 +1,12 @@
+use crate::processor::create_admin;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
     program_error::ProgramError,
     program_pack::{Pack, Sealed},
+    pubkey::Pubkey,
 };
 
 #[derive(BorshDeserialize, BorshSerialize, PartialEq, Debug)]
@@ -58,15 +63,194 @@ impl Pack for ResellingAuction {
     }
 }
 
+pub const CENTRAL_STATE_VERSION: u8 = 10;
+
+// Pause flags of the central state
+pub const PAUSE_CREATE: u64 = 1 << 0;
+pub const PAUSE_CLAIM: u64 = 1 << 1;
+pub const PAUSE_RESELL: u64 = 1 << 2;
+pub const PAUSE_CREATE_REVERSE: u64 = 1 << 3;
+pub const PAUSE_END_AUCTION: u64 = 1 << 4;
+pub const PAUSE_CREATE_V2: u64 = 1 << 5;
+pub const PAUSE_RENEW: u64 = 1 << 6;
+pub const PAUSE_RECLAIM_EXPIRED: u64 = 1 << 7;
+pub const PAUSE_PLACE_BID: u64 = 1 << 8;
+pub const PAUSE_ALL: u64 = 1 << 63;
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuthorityRole {
+    Admin,
+    ClaimAdmin,
+    CreateAdmin,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PendingAuthority {
+    pub role: AuthorityRole,
+    pub new_key: [u8; 32],
+    pub effective_at: i64, // Unix timestamp
+}
+
+impl PendingAuthority {
+    pub const LEN: usize = 1 + 32 + 8;
+}
+
+// Fees taken for the reselling of domain names, as configured on mainnet
+// | Tier | Percentage of payout    | Requirements   |
+// | ---- | ----------------------- | -------------- |
+// | 0    | 5%                      | None           |
+// | 1    | 3%                      | 10 FIDA        |
+// | 2    | 2%                      | 100 FIDA       |
+// | 3    | 1.5%                    | 500 FIDA       |
+// | 4    | 1%                      | 1,000 FIDA     |
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct ProgramConfig {
+    pub bonfida_fida_vault: [u8; 32],
+    pub bonfida_usdc_vault: [u8; 32],
+    pub bonfida_sol_vault: [u8; 32],
+    pub pyth_fida_price_acc: [u8; 32],
+    pub pyth_fida_fallback_price_acc: [u8; 32], // Used when the main feed is stale or uncertain
+    pub auction_max_length: u64,                // In seconds
+    pub end_auction_gap: u64,                   // In seconds
+    pub fees: [u64; 5],                         // In basis points, indexed by fee tier
+    pub fee_tiers: [u64; 4], // Amount of FIDA tokens (with precision) that the discount account needs to hold
+    pub authority_change_delay: u64, // In seconds
+    pub oracle_max_age: u64, // In slots since the last publication
+    pub oracle_max_confidence_bps: u64, // Confidence interval relative to the price, in basis points
+    pub referral_share_bps: u64, // Share of direct registrations paid to the referrer, in basis points
+    pub bid_increment_bps: u64, // Minimum raise over the top bid of primary auctions, in basis points
+    pub min_end_auction_gap: u64, // Bounds of the end gap sellers can choose, in seconds
+    pub max_end_auction_gap: u64,
+    pub min_bid_increment_bps: u64, // Bounds of the bid increment sellers can choose, in basis points
+    pub max_bid_increment_bps: u64,
+    pub reveal_length: u64, // Reveal period of sealed bid auctions, in seconds
+    pub accept_bid_window: u64, // Time sellers have to accept a bid below their reserve, in seconds
+    pub cancellation_penalty_bps: u64, // Paid by sellers cancelling an auction with bids, relative to the top bid
+    pub cancellation_compensation_bps: u64, // Share of the cancellation penalty paid to the top bidder
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 5 * 32 + 2 * 8 + 5 * 8 + 4 * 8 + 4 * 8 + 9 * 8;
+
+    pub fn check(&self) -> ProgramResult {
+        if self.fees.iter().any(|&f| f > 10_000)
+            || self.referral_share_bps > 10_000
+            || self.cancellation_penalty_bps > 10_000
+            || self.cancellation_compensation_bps > 10_000
+        {
+            msg!("Fees cannot exceed 100%");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.fee_tiers.windows(2).any(|w| w[0] >= w[1]) {
+            msg!("Fee tiers must be strictly increasing");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.auction_max_length == 0 || self.reveal_length == 0 {
+            msg!("The auction and reveal lengths cannot be zero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.oracle_max_age == 0 || self.oracle_max_confidence_bps == 0 {
+            msg!("The oracle guards cannot be zero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.min_end_auction_gap > self.max_end_auction_gap
+            || self.min_bid_increment_bps > self.max_bid_increment_bps
+            || self.max_bid_increment_bps > 10_000
+        {
+            msg!("Invalid auction parameter bounds");
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.check_auction_params(self.end_auction_gap, self.bid_increment_bps)
+    }
+
+    /// Checks the end gap and bid increment of an auction against the configured bounds
+    pub fn check_auction_params(
+        &self,
+        end_auction_gap: u64,
+        bid_increment_bps: u64,
+    ) -> ProgramResult {
+        if end_auction_gap < self.min_end_auction_gap || end_auction_gap > self.max_end_auction_gap
+        {
+            msg!(
+                "The end gap must be between {} and {} seconds",
+                self.min_end_auction_gap,
+                self.max_end_auction_gap
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        if bid_increment_bps < self.min_bid_increment_bps
+            || bid_increment_bps > self.max_bid_increment_bps
+        {
+            msg!(
+                "The bid increment must be between {} and {} basis points",
+                self.min_bid_increment_bps,
+                self.max_bid_increment_bps
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct CentralState {
     pub signer_nonce: u8,
+    pub version: u8,
+    pub admin: [u8; 32],
+    pub admin_claim_key: [u8; 32],
+    pub admin_create_key: [u8; 32],
+    pub config: ProgramConfig,
+    pub paused: u64,                                // Bitmask of paused instructions
+    pub pending_authorities: Vec<PendingAuthority>, // At most one per role
+}
+
+impl CentralState {
+    pub fn authority_mut(&mut self, role: AuthorityRole) -> &mut [u8; 32] {
+        match role {
+            AuthorityRole::Admin => &mut self.admin,
+            AuthorityRole::ClaimAdmin => &mut self.admin_claim_key,
+            AuthorityRole::CreateAdmin => &mut self.admin_create_key,
+        }
+    }
+
+    pub fn find_key(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[&program_id.to_bytes()], program_id)
+    }
+
+    pub fn from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        if account.owner != program_id || account.data_is_empty() {
+            msg!("The central state account is not initialized");
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if account.data_len() == 1 || account.data.borrow()[1] < CENTRAL_STATE_VERSION {
+            msg!("The central state has to be migrated with MigrateCentralState");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let state = Self::unpack_unchecked(&account.data.borrow())?;
+        if state.version != CENTRAL_STATE_VERSION {
+            msg!("Unsupported central state version {}", state.version);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let key = Pubkey::create_program_address(
+            &[&program_id.to_bytes(), &[state.signer_nonce]],
+            program_id,
+        )?;
+        if &key != account.key {
+            msg!("Invalid central state account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(state)
+    }
 }
 
 impl Sealed for CentralState {}
 
 impl Pack for CentralState {
-    const LEN: usize = 1;
+    const LEN: usize = 2 + 3 * 32 + ProgramConfig::LEN + 8 + 4 + 3 * PendingAuthority::LEN;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let mut pt = dst;
@@ -84,3 +268,590 @@ impl Pack for CentralState {
 pub struct ReverseLookup {
     pub name: String,
 }
+
+pub const MAX_ADMINS: usize = 10;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AdminSet {
+    pub threshold: u8,
+    pub proposal_count: u64,
+    pub admins: Vec<[u8; 32]>,
+}
+
+impl AdminSet {
+    pub const SEED: &'static [u8] = b"admin_set";
+
+    pub fn find_key(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED], program_id)
+    }
+
+    pub fn check(admins: &[[u8; 32]], threshold: u8) -> ProgramResult {
+        if admins.is_empty() || admins.len() > MAX_ADMINS {
+            msg!(
+                "The admin set must contain between 1 and {} keys",
+                MAX_ADMINS
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        if threshold == 0 || threshold as usize > admins.len() {
+            msg!("Invalid approval threshold");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if admins
+            .iter()
+            .enumerate()
+            .any(|(i, k)| admins[i + 1..].contains(k))
+        {
+            msg!("The admin set contains duplicate keys");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admins.contains(&key.to_bytes())
+    }
+}
+
+impl Sealed for AdminSet {}
+
+impl Pack for AdminSet {
+    const LEN: usize = 1 + 8 + 4 + MAX_ADMINS * 32;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    SetAdminSet {
+        admins: Vec<[u8; 32]>,
+        threshold: u8,
+    },
+    ResetAuction {
+        name: [u8; 32],
+    },
+    ClaimAdmin {
+        hashed_name: [u8; 32],
+        space: u32,
+        new_owner: [u8; 32],
+    },
+    TakeBack {
+        name: [u8; 32],
+        new_owner: [u8; 32],
+        reason: TakeBackReason,
+    },
+    CreateAdmin(create_admin::Params),
+    UpdateConfig(Box<ProgramConfig>),
+    SetPause {
+        flags: u64,
+        paused: bool,
+    },
+    UpdateBlocklist {
+        page: u8,
+        add: Vec<[u8; 32]>,
+        remove: Vec<[u8; 32]>,
+    },
+    SetPriceSchedule(PriceSchedule),
+    SetPaymentMint(PaymentMint),
+    ProposeAuthority {
+        role: AuthorityRole,
+        new_key: [u8; 32],
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Proposal {
+    pub index: u64,
+    pub executed: bool,
+    pub approvals: Vec<[u8; 32]>,
+    pub action: AdminAction,
+}
+
+impl Proposal {
+    pub const SEED: &'static [u8] = b"proposal";
+
+    pub fn find_key(program_id: &Pubkey, index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &index.to_le_bytes()], program_id)
+    }
+
+    // Proposals are sized for the action they carry and a full set of approvals
+    pub fn space(action: &AdminAction) -> usize {
+        8 + 1 + 4 + MAX_ADMINS * 32 + action.try_to_vec().unwrap().len()
+    }
+
+    // Approvals from keys that were removed from the admin set do not count
+    pub fn approval_count(&self, admin_set: &AdminSet) -> usize {
+        self.approvals
+            .iter()
+            .filter(|k| admin_set.admins.contains(k))
+            .count()
+    }
+}
+
+pub const MAX_RESERVATION_REASON_LEN: usize = 128;
+
+// Written by CreateAdmin for every reserved name
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct Reservation {
+    pub name_account: [u8; 32],
+    pub recipient: [u8; 32],
+    pub proposal: [u8; 32], // The approved proposal that reserved the name
+    pub reserved_at: i64,
+    pub reason: String,
+}
+
+impl Reservation {
+    pub const SEED: &'static [u8] = b"reservation";
+
+    pub fn find_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &name_account.to_bytes()], program_id)
+    }
+
+    pub fn space(reason: &str) -> usize {
+        3 * 32 + 8 + 4 + reason.len()
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum TakeBackReason {
+    StuckResell,
+    Squatting,
+    Legal,
+    Other,
+}
+
+// Written by TakeBack, one per name, keeps the latest takeback
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct TakeBackRecord {
+    pub name_account: [u8; 32],
+    pub new_owner: [u8; 32],
+    pub proposal: [u8; 32], // The approved proposal that took the name back
+    pub reason: TakeBackReason,
+    pub taken_back_at: i64,
+    pub count: u64, // Number of takebacks of this name
+}
+
+impl TakeBackRecord {
+    pub const SEED: &'static [u8] = b"take_back";
+
+    pub fn find_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &name_account.to_bytes()], program_id)
+    }
+}
+
+impl Sealed for TakeBackRecord {}
+
+impl Pack for TakeBackRecord {
+    const LEN: usize = 3 * 32 + 1 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+pub const MAX_BLOCKLIST_PAGE_LEN: usize = 128;
+
+// Hashed names which cannot be registered, a name belongs to the page given by the first byte of its hash
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BlocklistPage {
+    pub page: u8,
+    pub hashed_names: Vec<[u8; 32]>,
+}
+
+impl BlocklistPage {
+    pub const SEED: &'static [u8] = b"blocklist";
+
+    pub fn find_key(program_id: &Pubkey, page: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &[page]], program_id)
+    }
+
+    pub fn page_of(hashed_name: &[u8]) -> u8 {
+        hashed_name[0]
+    }
+}
+
+impl Sealed for BlocklistPage {}
+
+impl Pack for BlocklistPage {
+    const LEN: usize = 1 + 4 + MAX_BLOCKLIST_PAGE_LEN * 32;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+pub const MAX_PRICE_TIERS: usize = 16;
+pub const MAX_VOLUME_DISCOUNTS: usize = 4;
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PriceTier {
+    pub length: u8,     // Grapheme length of the names priced by this tier
+    pub price_usd: u64, // USD with 6 decimals
+    pub override_price_usd: Option<u64>, // Replaces the tier price while set
+}
+
+// Discount on the names bought together in a CreateV2Batch
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct VolumeDiscount {
+    pub min_names: u8,
+    pub discount_percent: u8,
+}
+
+// Prices of the domain names by length, the default schedule is used until admins set one
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PriceSchedule {
+    pub default_price_usd: u64, // Price of the names without a tier
+    pub tiers: Vec<PriceTier>,
+    pub premium_root: [u8; 32], // Merkle root of the premium names, leaves sorted by hashed name
+    pub premium_count: u32,     // Number of premium names, zero when there is no premium list
+    pub release_premium: Option<ReleasePremium>, // Premium of the names released by an auction
+    pub volume_discounts: Vec<VolumeDiscount>,
+}
+
+impl PriceSchedule {
+    pub const SEED: &'static [u8] = b"price_schedule";
+
+    pub fn find_key(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED], program_id)
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        if self.tiers.len() > MAX_PRICE_TIERS {
+            msg!(
+                "The price schedule cannot have more than {} tiers",
+                MAX_PRICE_TIERS
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self
+            .tiers
+            .iter()
+            .enumerate()
+            .any(|(i, t)| self.tiers[i + 1..].iter().any(|o| o.length == t.length))
+        {
+            msg!("The price schedule contains duplicate tiers");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if let Some(release_premium) = &self.release_premium {
+            release_premium.check()?;
+        }
+        if self.volume_discounts.len() > MAX_VOLUME_DISCOUNTS
+            || self
+                .volume_discounts
+                .iter()
+                .any(|d| d.discount_percent > 100)
+        {
+            msg!("Invalid volume discounts");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.default_price_usd == 0
+            || self
+                .tiers
+                .iter()
+                .any(|t| t.price_usd == 0 || t.override_price_usd == Some(0))
+        {
+            msg!("Prices cannot be zero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    pub fn get_usd_price(&self, len: usize) -> u64 {
+        self.tiers
+            .iter()
+            .find(|t| t.length as usize == len)
+            .map(|t| t.override_price_usd.unwrap_or(t.price_usd))
+            .unwrap_or(self.default_price_usd)
+    }
+
+    // The largest discount among the tiers reached by the number of names
+    pub fn get_volume_discount_percent(&self, names: usize) -> u8 {
+        self.volume_discounts
+            .iter()
+            .filter(|d| d.min_names as usize <= names)
+            .map(|d| d.discount_percent)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Default for PriceSchedule {
+    fn default() -> Self {
+        let tier = |length, price: u64| PriceTier {
+            length,
+            price_usd: price * 1_000_000,
+            override_price_usd: None,
+        };
+        Self {
+            default_price_usd: 20_000_000,
+            tiers: vec![tier(1, 750), tier(2, 700), tier(3, 640), tier(4, 160)],
+            premium_root: [0; 32],
+            premium_count: 0,
+            release_premium: None,
+            volume_discounts: vec![],
+        }
+    }
+}
+
+impl Sealed for PriceSchedule {}
+
+impl Pack for PriceSchedule {
+    const LEN: usize = 8
+        + 4
+        + MAX_PRICE_TIERS * (1 + 8 + 9)
+        + 32
+        + 4
+        + 1
+        + ReleasePremium::LEN
+        + 4
+        + MAX_VOLUME_DISCOUNTS * 2;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum PremiumDecay {
+    Linear,
+    Exponential { half_life: u64 }, // In seconds
+}
+
+// Premium added to the price of a name when it is released, it decays to zero over the window
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct ReleasePremium {
+    pub start_premium_usd: u64, // USD with 6 decimals
+    pub window: u64,            // In seconds
+    pub decay: PremiumDecay,
+}
+
+impl ReleasePremium {
+    pub const LEN: usize = 8 + 8 + 1 + 8;
+
+    pub fn check(&self) -> ProgramResult {
+        if self.window == 0 {
+            msg!("The release premium window cannot be zero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if let PremiumDecay::Exponential { half_life: 0 } = self.decay {
+            msg!("The release premium half life cannot be zero");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    pub fn get_premium(&self, elapsed: i64) -> u64 {
+        if elapsed < 0 {
+            return self.start_premium_usd;
+        }
+        let elapsed = elapsed as u64;
+        if elapsed >= self.window {
+            return 0;
+        }
+        match self.decay {
+            PremiumDecay::Linear => {
+                ((self.start_premium_usd as u128) * ((self.window - elapsed) as u128)
+                    / (self.window as u128)) as u64
+            }
+            PremiumDecay::Exponential { half_life } => {
+                // Halved for every elapsed half life, interpolated linearly in between
+                let halvings = elapsed / half_life;
+                if halvings >= 64 {
+                    return 0;
+                }
+                let premium = self.start_premium_usd >> halvings;
+                let remainder = elapsed % half_life;
+                premium - ((premium as u128) * (remainder as u128) / (2 * half_life as u128)) as u64
+            }
+        }
+    }
+}
+
+// Proves that the premium list contains the leaf at `index`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PremiumProof {
+    pub hashed_name: [u8; 32],
+    pub price_usd: u64,
+    pub index: u32,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+// A mint accepted by CreateV2, prices are converted from USD with the Pyth feed of the mint
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct PaymentMint {
+    pub mint: [u8; 32],
+    pub vault: [u8; 32],                  // Token account receiving the payments
+    pub pyth_price_acc: Option<[u8; 32]>, // None for USD stablecoins
+    pub enabled: bool,
+}
+
+impl PaymentMint {
+    pub const SEED: &'static [u8] = b"payment_mint";
+
+    pub fn find_key(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &mint.to_bytes()], program_id)
+    }
+}
+
+impl Sealed for PaymentMint {}
+
+impl Pack for PaymentMint {
+    const LEN: usize = 32 + 32 + 1 + 32 + 1;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+pub const REGISTRATION_PERIOD: i64 = 365 * 24 * 3600; // In seconds, bought by each year of renewal
+pub const GRACE_PERIOD: i64 = 30 * 24 * 3600; // In seconds, during which only the owner can renew
+pub const MAX_RENEWAL_YEARS: u8 = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Registration {
+    pub name_account: [u8; 32],
+    pub expires_at: i64,
+}
+
+impl Registration {
+    pub const SEED: &'static [u8] = b"registration";
+
+    pub fn find_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &name_account.to_bytes()], program_id)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn is_past_grace_period(&self, now: i64) -> bool {
+        now >= self.expires_at.saturating_add(GRACE_PERIOD)
+    }
+}
+
+impl Sealed for Registration {}
+
+impl Pack for Registration {
+    const LEN: usize = 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+/// Discount signed by the admin with an Ed25519 program instruction preceding CreateV2
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Voucher {
+    pub discount_percent: u8,
+    pub hashed_name: Option<[u8; 32]>, // None when the voucher is valid for any name
+    pub expires_at: i64,
+    pub nonce: u64, // Each voucher can only be used once
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct UsedVoucher {
+    pub nonce: u64,
+    pub used_by: [u8; 32],
+    pub used_at: i64,
+}
+
+impl UsedVoucher {
+    pub const SEED: &'static [u8] = b"used_voucher";
+
+    pub fn find_key(program_id: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &nonce.to_le_bytes()], program_id)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct ReferralStats {
+    pub referrer: [u8; 32], // The token account receiving the referral shares
+    pub referrals: u64,
+    pub total_paid: u64, // In tokens of the referrer account mint
+}
+
+impl ReferralStats {
+    pub const SEED: &'static [u8] = b"referral";
+
+    pub fn find_key(program_id: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &referrer.to_bytes()], program_id)
+    }
+}
+
+impl Sealed for ReferralStats {}
+
+impl Pack for ReferralStats {
+    const LEN: usize = 32 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
+
+impl Sealed for UsedVoucher {}
+
+impl Pack for UsedVoucher {
+    const LEN: usize = 8 + 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let mut pt = dst;
+        self.serialize(&mut pt).unwrap();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let mut pt = src;
+        let res = Self::deserialize(&mut pt)?;
+        Ok(res)
+    }
+}
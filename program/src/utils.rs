@@ -1,26 +1,40 @@
+use bonfida_utils::fp_math::fp32_div;
 use borsh::BorshSerialize;
 use solana_program::{
     account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
+    ed25519_program,
     entrypoint::ProgramResult,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
-    system_instruction::create_account,
-    sysvar::Sysvar,
-};
-use spl_auction::{
-    instruction::{claim_bid_instruction, create_auction_instruction, start_auction_instruction},
-    processor::{ClaimBidArgs, CreateAuctionArgs, PriceFloor, StartAuctionArgs, WinnerLimit},
+    system_instruction::{allocate, assign, create_account, transfer as transfer_lamports},
+    sysvar::{
+        self,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
 };
 use spl_name_service::{instruction::NameRegistryInstruction, state::NameRecordHeader};
+use spl_token::{instruction::transfer, state::Account};
 
 use crate::{
-    processor::{END_AUCTION_GAP, TOKEN_MINT},
-    state::{NameAuction, ReverseLookup},
+    error::NameAuctionError,
+    oracle::get_oracle_price_fp32,
+    processor::USDC_MINT,
+    state::{
+        AdminAction, AdminSet, BlocklistPage, CentralState, PaymentMint, PremiumProof,
+        PriceSchedule, ProgramConfig, Proposal, ReferralStats, Registration, ResellingAuction,
+        ReverseLookup, Voucher, PAUSE_ALL,
+    },
 };
 
+use std::convert::TryFrom;
 use unicode_segmentation::UnicodeSegmentation;
 
 pub struct Cpi {}
@@ -36,11 +50,38 @@ impl Cpi {
         space: usize,
     ) -> ProgramResult {
         let rent = Rent::from_account_info(rent_sysvar_account)?;
+        let lamports = rent.minimum_balance(space);
+
+        // Anyone can send lamports to the address beforehand, which makes create_account fail
+        if account_to_create.lamports() > 0 {
+            let missing_lamports = lamports.saturating_sub(account_to_create.lamports());
+            if missing_lamports > 0 {
+                invoke(
+                    &transfer_lamports(fee_payer.key, account_to_create.key, missing_lamports),
+                    &[
+                        system_program.clone(),
+                        fee_payer.clone(),
+                        account_to_create.clone(),
+                    ],
+                )?;
+            }
+            let accounts = [system_program.clone(), account_to_create.clone()];
+            invoke_signed(
+                &allocate(account_to_create.key, space as u64),
+                &accounts,
+                &[signer_seeds],
+            )?;
+            return invoke_signed(
+                &assign(account_to_create.key, program_id),
+                &accounts,
+                &[signer_seeds],
+            );
+        }
 
         let create_state_instruction = create_account(
             fee_payer.key,
             account_to_create.key,
-            rent.minimum_balance(NameAuction::LEN),
+            lamports,
             space as u64,
             program_id,
         );
@@ -56,141 +97,6 @@ impl Cpi {
         )
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn create_auction<'a>(
-        auction_program: &AccountInfo<'a>,
-        rent_sysvar_account: &AccountInfo<'a>,
-        system_account: &AccountInfo<'a>,
-        auction_account: &AccountInfo<'a>,
-        fee_payer: &AccountInfo<'a>,
-        end_auction_at: Option<u64>,
-        authority: &AccountInfo<'a>,
-        buy_now_account: Option<&AccountInfo<'a>>,
-        resource: Pubkey,
-        minimum_price: u64,
-        signer_seeds: &[&[u8]],
-        max_price: Option<u64>,
-    ) -> ProgramResult {
-        let buy_now_pubkey: Option<Pubkey> = buy_now_account.map(|account| *account.key);
-
-        let create_auction_instruction = create_auction_instruction(
-            *auction_program.key,
-            *fee_payer.key,
-            *authority.key,
-            buy_now_pubkey,
-            CreateAuctionArgs {
-                winners: WinnerLimit::Capped(1),
-                end_auction_at: end_auction_at.map(|n| n as i64),
-                end_auction_gap: Some(END_AUCTION_GAP as i64),
-                token_mint: TOKEN_MINT,
-                resource,
-                price_floor: PriceFloor::MinimumPrice([minimum_price, 0, 0, 0]),
-                max_price,
-            },
-        );
-
-        let mut account_infos = vec![
-            auction_program.clone(),
-            fee_payer.clone(),
-            auction_account.clone(),
-            rent_sysvar_account.clone(),
-            system_account.clone(),
-            authority.clone(),
-        ];
-
-        if let Some(a) = buy_now_account {
-            account_infos.push(a.clone())
-        }
-
-        invoke_signed(&create_auction_instruction, &account_infos, &[signer_seeds])
-    }
-
-    pub fn start_auction<'a>(
-        auction_program: &AccountInfo<'a>,
-        clock_sysvar_account: &AccountInfo<'a>,
-        auction_account: &AccountInfo<'a>,
-        authority: &AccountInfo<'a>,
-        resource: Pubkey,
-        signer_seeds: &[&[u8]],
-    ) -> ProgramResult {
-        let create_auction_instruction = start_auction_instruction(
-            *auction_program.key,
-            *authority.key,
-            StartAuctionArgs { resource },
-        );
-
-        invoke_signed(
-            &create_auction_instruction,
-            &[
-                auction_program.clone(),
-                authority.clone(),
-                auction_account.clone(),
-                clock_sysvar_account.clone(),
-            ],
-            &[signer_seeds],
-        )
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    pub fn claim_auction<'a>(
-        spl_token_program: &AccountInfo<'a>,
-        auction_program: &AccountInfo<'a>,
-        clock_sysvar_account: &AccountInfo<'a>,
-        auction_account: &AccountInfo<'a>,
-        destination_token_account: &AccountInfo<'a>,
-        winner_account: &AccountInfo<'a>,
-        winner_pot_account: &AccountInfo<'a>,
-        winner_pot_token_account: &AccountInfo<'a>,
-        quote_mint: &AccountInfo<'a>,
-        authority: &AccountInfo<'a>,
-        bonfida_vault: &AccountInfo<'a>,
-        buy_now: &AccountInfo<'a>,
-        bonfida_sol_vault: &AccountInfo<'a>,
-        referrer: Option<&AccountInfo<'a>>,
-        resource: Pubkey,
-        signer_seeds: &[&[u8]],
-        fee_percentage: u64,
-    ) -> ProgramResult {
-        let claim_auction_instruction = claim_bid_instruction(
-            *auction_program.key,
-            *destination_token_account.key,
-            *authority.key,
-            *winner_account.key,
-            *winner_pot_token_account.key,
-            *quote_mint.key,
-            *bonfida_vault.key,
-            *buy_now.key,
-            *bonfida_sol_vault.key,
-            referrer.map(|acc| *acc.key),
-            ClaimBidArgs {
-                resource,
-                fee_percentage,
-            },
-        );
-
-        let mut accounts = vec![
-            auction_program.clone(),
-            destination_token_account.clone(),
-            winner_pot_token_account.clone(),
-            winner_pot_account.clone(),
-            authority.clone(),
-            auction_account.clone(),
-            winner_account.clone(),
-            quote_mint.clone(),
-            clock_sysvar_account.clone(),
-            spl_token_program.clone(),
-            bonfida_vault.clone(),
-            buy_now.clone(),
-            bonfida_sol_vault.clone(),
-        ];
-
-        if let Some(referrer) = referrer {
-            accounts.push(referrer.clone());
-        }
-
-        invoke_signed(&claim_auction_instruction, &accounts, &[signer_seeds])
-    }
-
     #[allow(clippy::too_many_arguments)]
     pub fn create_name_account<'a>(
         name_service_program: &AccountInfo<'a>,
@@ -303,6 +209,44 @@ impl Cpi {
         Ok(())
     }
 
+    // The owner of the parent name can transfer any of its subdomains
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_name_account_by_parent_owner<'a>(
+        name_service_program: &AccountInfo<'a>,
+        parent_owner_account: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        name_class_account: &AccountInfo<'a>,
+        parent_name_account: &AccountInfo<'a>,
+        new_owner_key: &Pubkey,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let instruction_data = NameRegistryInstruction::Transfer {
+            new_owner: *new_owner_key,
+        };
+        let transfer_name_instruction = Instruction {
+            program_id: *name_service_program.key,
+            accounts: vec![
+                AccountMeta::new(*name_account.key, false),
+                AccountMeta::new_readonly(*parent_owner_account.key, true),
+                AccountMeta::new_readonly(*name_class_account.key, false),
+                AccountMeta::new_readonly(*parent_name_account.key, false),
+            ],
+            data: instruction_data.try_to_vec().unwrap(),
+        };
+
+        invoke_signed(
+            &transfer_name_instruction,
+            &[
+                name_service_program.clone(),
+                name_account.clone(),
+                parent_owner_account.clone(),
+                name_class_account.clone(),
+                parent_name_account.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
     pub fn transfer_name_account<'a>(
         name_service_program: &AccountInfo<'a>,
         old_owner_account: &AccountInfo<'a>,
@@ -340,33 +284,34 @@ impl Cpi {
         }
     }
 
-    pub fn end_auction<'a>(
-        auction_program: &AccountInfo<'a>,
-        authority: &AccountInfo<'a>,
-        auction: &AccountInfo<'a>,
-        clock_sysvar_account: &AccountInfo<'a>,
-        resource: Pubkey,
-        signer_seeds: &[&[u8]],
+    pub fn transfer_tokens<'a>(
+        spl_token_program: &AccountInfo<'a>,
+        source: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        owner: &AccountInfo<'a>,
+        amount: u64,
+        signer_seeds: Option<&[&[u8]]>,
     ) -> ProgramResult {
-        let end_auction_instruction = spl_auction::instruction::end_auction_instruction(
-            *auction_program.key,
-            *authority.key,
-            spl_auction::processor::EndAuctionArgs {
-                resource,
-                reveal: None,
-            },
-        );
+        let transfer_instruction = transfer(
+            &spl_token::ID,
+            source.key,
+            destination.key,
+            owner.key,
+            &[],
+            amount,
+        )?;
+        let account_infos = [
+            spl_token_program.clone(),
+            source.clone(),
+            destination.clone(),
+            owner.clone(),
+        ];
 
-        invoke_signed(
-            &end_auction_instruction,
-            &[
-                auction_program.clone(),
-                authority.clone(),
-                auction.clone(),
-                clock_sysvar_account.clone(),
-            ],
-            &[signer_seeds],
-        )
+        if let Some(seeds) = signer_seeds {
+            invoke_signed(&transfer_instruction, &account_infos, &[seeds])
+        } else {
+            invoke(&transfer_instruction, &account_infos)
+        }
     }
 }
 
@@ -391,15 +336,439 @@ pub fn check_signer(account: &AccountInfo) -> ProgramResult {
     Ok(())
 }
 
-pub fn get_usd_price(len: usize) -> u64 {
-    let multiplier = match len {
-        1 => 750,
-        2 => 700,
-        3 => 640,
-        4 => 160,
-        _ => 20,
+// The seller of a reselling auction is the owner of the account receiving the proceeds
+pub fn check_reselling_seller(
+    program_id: &Pubkey,
+    name_account: &Pubkey,
+    reselling_state: &AccountInfo,
+    destination_token: &AccountInfo,
+    seller: &AccountInfo,
+) -> ProgramResult {
+    let (reselling_state_key, _) =
+        Pubkey::find_program_address(&[&name_account.to_bytes(), &[1u8, 1u8]], program_id);
+    check_account_key(reselling_state, &reselling_state_key)?;
+    check_account_owner(reselling_state, program_id)?;
+    let reselling_state = ResellingAuction::unpack_unchecked(&reselling_state.data.borrow())?;
+    check_account_key(
+        destination_token,
+        &Pubkey::new(&reselling_state.token_destination_account),
+    )?;
+    check_account_owner(destination_token, &spl_token::ID)?;
+    let destination_token = Account::unpack(&destination_token.data.borrow())?;
+    check_account_key(seller, &destination_token.owner)?;
+    check_signer(seller)
+}
+
+// Consumes a proposal approved by the admin set, it has to match the action being executed
+pub fn execute_proposal(
+    program_id: &Pubkey,
+    admin_set_account: &AccountInfo,
+    proposal_account: &AccountInfo,
+    action: &AdminAction,
+) -> ProgramResult {
+    check_account_key(admin_set_account, &AdminSet::find_key(program_id).0)?;
+    check_account_owner(admin_set_account, program_id)?;
+    check_account_owner(proposal_account, program_id)?;
+
+    let admin_set = AdminSet::unpack_unchecked(&admin_set_account.data.borrow())?;
+    let mut proposal: Proposal = try_from_slice_unchecked(&proposal_account.data.borrow())?;
+    check_account_key(
+        proposal_account,
+        &Proposal::find_key(program_id, proposal.index).0,
+    )?;
+
+    if proposal.executed {
+        return Err(NameAuctionError::ProposalAlreadyExecuted.into());
+    }
+    if &proposal.action != action {
+        msg!("The proposal does not match this instruction");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if proposal.approval_count(&admin_set) < admin_set.threshold as usize {
+        return Err(NameAuctionError::ThresholdNotMet.into());
+    }
+
+    proposal.executed = true;
+    let mut pt: &mut [u8] = &mut proposal_account.data.borrow_mut();
+    proposal.serialize(&mut pt)?;
+
+    Ok(())
+}
+
+// Rejects the instruction when its pause flag or the global one is set
+pub fn check_not_paused(program_id: &Pubkey, accounts: &[AccountInfo], flag: u64) -> ProgramResult {
+    let (central_state_key, _) = CentralState::find_key(program_id);
+    let central_state_account = accounts
+        .iter()
+        .find(|a| a.key == &central_state_key)
+        .ok_or_else(|| {
+            msg!("The central state account is missing");
+            ProgramError::NotEnoughAccountKeys
+        })?;
+    let central_state = CentralState::from_account_info(central_state_account, program_id)?;
+    if central_state.paused & (flag | PAUSE_ALL) != 0 {
+        return Err(NameAuctionError::Paused.into());
+    }
+    Ok(())
+}
+
+// The blocklist page of the name has to be provided, it can be uninitialized when nothing was blocked on it
+pub fn check_not_blocked(
+    program_id: &Pubkey,
+    blocklist_page_account: &AccountInfo,
+    hashed_name: &[u8],
+) -> ProgramResult {
+    let page = BlocklistPage::page_of(hashed_name);
+    check_account_key(
+        blocklist_page_account,
+        &BlocklistPage::find_key(program_id, page).0,
+    )?;
+    if blocklist_page_account.data_is_empty() {
+        return Ok(());
+    }
+    check_account_owner(blocklist_page_account, program_id)?;
+    let blocklist_page = BlocklistPage::unpack_from_slice(&blocklist_page_account.data.borrow())?;
+    if blocklist_page
+        .hashed_names
+        .iter()
+        .any(|h| h.as_ref() == hashed_name)
+    {
+        return Err(NameAuctionError::BlockedName.into());
+    }
+    Ok(())
+}
+
+// Falls back to the default schedule while the price schedule account is uninitialized
+pub fn load_price_schedule(
+    program_id: &Pubkey,
+    price_schedule_account: &AccountInfo,
+) -> Result<PriceSchedule, ProgramError> {
+    check_account_key(
+        price_schedule_account,
+        &PriceSchedule::find_key(program_id).0,
+    )?;
+    if price_schedule_account.data_is_empty() {
+        return Ok(PriceSchedule::default());
+    }
+    check_account_owner(price_schedule_account, program_id)?;
+    PriceSchedule::unpack_from_slice(&price_schedule_account.data.borrow())
+}
+
+pub fn get_usd_price(
+    program_id: &Pubkey,
+    price_schedule_account: &AccountInfo,
+    len: usize,
+) -> Result<u64, ProgramError> {
+    Ok(load_price_schedule(program_id, price_schedule_account)?.get_usd_price(len))
+}
+
+// Layout of the Ed25519 program instruction data for a single signature
+const ED25519_SIGNATURE_OFFSETS_START: usize = 2;
+const ED25519_SIGNATURE_OFFSETS_LEN: usize = 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+const ED25519_SIGNATURE_LEN: usize = 64;
+
+// The instruction preceding the current one must verify the admin signature over the voucher
+pub fn verify_voucher(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    voucher: &Voucher,
+) -> ProgramResult {
+    check_account_key(instructions_sysvar, &sysvar::instructions::id())?;
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    if current_index == 0 {
+        msg!("The voucher signature must be verified by the previous instruction");
+        return Err(NameAuctionError::InvalidVoucher.into());
+    }
+    let ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    if ix.program_id != ed25519_program::id() {
+        msg!("The voucher signature must be verified by the previous instruction");
+        return Err(NameAuctionError::InvalidVoucher.into());
+    }
+
+    let data = &ix.data;
+    if data.len() < ED25519_SIGNATURE_OFFSETS_START + ED25519_SIGNATURE_OFFSETS_LEN || data[0] != 1
+    {
+        msg!("Expected a single signature");
+        return Err(NameAuctionError::InvalidVoucher.into());
+    }
+    let offsets = data[ED25519_SIGNATURE_OFFSETS_START
+        ..ED25519_SIGNATURE_OFFSETS_START + ED25519_SIGNATURE_OFFSETS_LEN]
+        .chunks(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect::<Vec<_>>();
+    let (signature_offset, signature_ix, pubkey_offset, pubkey_ix) =
+        (offsets[0], offsets[1], offsets[2], offsets[3]);
+    let (message_offset, message_size, message_ix) = (offsets[4], offsets[5], offsets[6]);
+    // Offsets must point into the Ed25519 instruction itself
+    if [signature_ix, pubkey_ix, message_ix]
+        .iter()
+        .any(|&i| i != u16::MAX)
+    {
+        msg!("The voucher signature must be self contained");
+        return Err(NameAuctionError::InvalidVoucher.into());
+    }
+
+    let read = |offset: u16, len: usize| data.get(offset as usize..offset as usize + len);
+    let pubkey = read(pubkey_offset, ED25519_PUBKEY_LEN);
+    let message = read(message_offset, message_size as usize);
+    if read(signature_offset, ED25519_SIGNATURE_LEN).is_none()
+        || pubkey != Some(signer.as_ref())
+        || message != Some(&voucher.try_to_vec()?)
+    {
+        msg!("The voucher is not signed by the admin");
+        return Err(NameAuctionError::InvalidVoucher.into());
+    }
+
+    Ok(())
+}
+
+// Creates the registration record of a name if needed and sets its expiry
+#[allow(clippy::too_many_arguments)]
+pub fn set_registration<'a>(
+    program_id: &Pubkey,
+    system_program: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    registration_account: &AccountInfo<'a>,
+    rent_sysvar: &AccountInfo<'a>,
+    name_account: &Pubkey,
+    expires_at: i64,
+) -> ProgramResult {
+    let (registration_key, nonce) = Registration::find_key(program_id, name_account);
+    check_account_key(registration_account, &registration_key)?;
+    if registration_account.data_is_empty() {
+        Cpi::create_account(
+            program_id,
+            system_program,
+            fee_payer,
+            registration_account,
+            rent_sysvar,
+            &[Registration::SEED, &name_account.to_bytes(), &[nonce]],
+            Registration::LEN,
+        )?;
+    } else {
+        check_account_owner(registration_account, program_id)?;
+    }
+    Registration {
+        name_account: name_account.to_bytes(),
+        expires_at,
+    }
+    .pack_into_slice(&mut registration_account.data.borrow_mut());
+    Ok(())
+}
+
+// Pays Bonfida, the referrer takes its share of the amount before the vault
+#[allow(clippy::too_many_arguments)]
+pub fn pay_with_referral<'a>(
+    program_id: &Pubkey,
+    spl_token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent_sysvar: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    buyer: &Pubkey,
+    source: &AccountInfo<'a>,
+    source_authority: &AccountInfo<'a>,
+    signer_seeds: Option<&[&[u8]]>,
+    vault: &AccountInfo<'a>,
+    referrer: Option<(&AccountInfo<'a>, &AccountInfo<'a>)>,
+    amount: u64,
+    names: u64,
+    config: &ProgramConfig,
+) -> ProgramResult {
+    let mut vault_amount = amount;
+    if let Some((referrer_token, referral_stats)) = referrer {
+        check_account_owner(referrer_token, &spl_token::ID)?;
+        if &Account::unpack(&referrer_token.data.borrow())?.owner == buyer {
+            msg!("Buyers cannot refer themselves");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (referral_stats_key, nonce) = ReferralStats::find_key(program_id, referrer_token.key);
+        check_account_key(referral_stats, &referral_stats_key)?;
+        let mut stats = if referral_stats.data_is_empty() {
+            Cpi::create_account(
+                program_id,
+                system_program,
+                fee_payer,
+                referral_stats,
+                rent_sysvar,
+                &[
+                    ReferralStats::SEED,
+                    &referrer_token.key.to_bytes(),
+                    &[nonce],
+                ],
+                ReferralStats::LEN,
+            )?;
+            ReferralStats {
+                referrer: referrer_token.key.to_bytes(),
+                referrals: 0,
+                total_paid: 0,
+            }
+        } else {
+            check_account_owner(referral_stats, program_id)?;
+            ReferralStats::unpack_from_slice(&referral_stats.data.borrow())?
+        };
+
+        let share = ((amount as u128) * (config.referral_share_bps as u128) / 10_000) as u64;
+        stats.referrals = stats
+            .referrals
+            .checked_add(names)
+            .ok_or(ProgramError::InvalidArgument)?;
+        stats.total_paid = stats
+            .total_paid
+            .checked_add(share)
+            .ok_or(ProgramError::InvalidArgument)?;
+        stats.pack_into_slice(&mut referral_stats.data.borrow_mut());
+
+        if share != 0 {
+            Cpi::transfer_tokens(
+                spl_token_program,
+                source,
+                referrer_token,
+                source_authority,
+                share,
+                signer_seeds,
+            )?;
+        }
+        vault_amount -= share;
+    }
+
+    Cpi::transfer_tokens(
+        spl_token_program,
+        source,
+        vault,
+        source_authority,
+        vault_amount,
+        signer_seeds,
+    )
+}
+
+// USDC is accepted with the vault of the config until admins set its payment mint
+pub fn load_payment_mint(
+    program_id: &Pubkey,
+    payment_mint_account: &AccountInfo,
+    mint: &Pubkey,
+    central_state: &CentralState,
+) -> Result<PaymentMint, ProgramError> {
+    check_account_key(
+        payment_mint_account,
+        &PaymentMint::find_key(program_id, mint).0,
+    )?;
+    let payment_mint = if payment_mint_account.data_is_empty() && mint == &USDC_MINT {
+        PaymentMint {
+            mint: USDC_MINT.to_bytes(),
+            vault: central_state.config.bonfida_usdc_vault,
+            pyth_price_acc: None,
+            enabled: true,
+        }
+    } else {
+        check_account_owner(payment_mint_account, program_id)?;
+        PaymentMint::unpack_from_slice(&payment_mint_account.data.borrow())?
     };
-    multiplier * 1_000_000
+    if !payment_mint.enabled {
+        msg!("This mint is not accepted for payments");
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(payment_mint)
+}
+
+// Converts a USD amount (6 decimals) into an amount of the payment mint
+pub fn get_quote_amount(
+    program_id: &Pubkey,
+    usd_amount: u64,
+    payment_mint: &PaymentMint,
+    mint_decimals: u8,
+    pyth_price_account: &AccountInfo,
+    config: &ProgramConfig,
+) -> Result<u64, ProgramError> {
+    let amount = match payment_mint.pyth_price_acc {
+        Some(pyth_price_acc) => {
+            check_account_key(pyth_price_account, &Pubkey::new(&pyth_price_acc))?;
+            let price = get_oracle_price_fp32(
+                program_id,
+                pyth_price_account,
+                None,
+                mint_decimals,
+                6,
+                config,
+            )?;
+            fp32_div(usd_amount, price)
+        }
+        None => (usd_amount as u128)
+            .checked_mul(10u128.pow(mint_decimals as u32))
+            .map(|a| a / 1_000_000)
+            .and_then(|a| u64::try_from(a).ok()),
+    };
+    amount.ok_or(ProgramError::InvalidArgument)
+}
+
+pub fn premium_leaf(hashed_name: &[u8], price_usd: u64) -> [u8; 32] {
+    hashv(&[&[0], hashed_name, &price_usd.to_le_bytes()]).to_bytes()
+}
+
+pub fn premium_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+// The last node of a level is carried to the next one when it has no sibling
+// (div_ceil is not available on the BPF toolchain)
+#[allow(unknown_lints, clippy::manual_div_ceil)]
+pub fn verify_premium_proof(root: &[u8; 32], count: u32, proof: &PremiumProof) -> bool {
+    if proof.index >= count {
+        return false;
+    }
+    let mut hash = premium_leaf(&proof.hashed_name, proof.price_usd);
+    let mut index = proof.index;
+    let mut width = count;
+    let mut siblings = proof.siblings.iter();
+    while width > 1 {
+        if index % 2 == 1 {
+            match siblings.next() {
+                Some(s) => hash = premium_node(s, &hash),
+                None => return false,
+            }
+        } else if index + 1 < width {
+            match siblings.next() {
+                Some(s) => hash = premium_node(&hash, s),
+                None => return false,
+            }
+        }
+        index /= 2;
+        width = (width + 1) / 2;
+    }
+    siblings.next().is_none() && &hash == root
+}
+
+// Returns the premium price of the name, or None when the proofs show that it is not in the list:
+// either the leaf of the name, or the leaves around where it would be
+pub fn get_premium_price(
+    price_schedule: &PriceSchedule,
+    hashed_name: &[u8],
+    proofs: &[PremiumProof],
+) -> Result<Option<u64>, ProgramError> {
+    let count = price_schedule.premium_count;
+    if count == 0 {
+        return Ok(None);
+    }
+    if !proofs
+        .iter()
+        .all(|p| verify_premium_proof(&price_schedule.premium_root, count, p))
+    {
+        return Err(NameAuctionError::InvalidPremiumProof.into());
+    }
+    match proofs {
+        [p] if p.hashed_name.as_ref() == hashed_name => Ok(Some(p.price_usd)),
+        [p] if p.index == 0 && hashed_name < p.hashed_name.as_ref() => Ok(None),
+        [p] if p.index == count - 1 && hashed_name > p.hashed_name.as_ref() => Ok(None),
+        [l, r]
+            if r.index == l.index + 1
+                && l.hashed_name.as_ref() < hashed_name
+                && hashed_name < r.hashed_name.as_ref() =>
+        {
+            Ok(None)
+        }
+        _ => Err(NameAuctionError::InvalidPremiumProof.into()),
+    }
 }
 
 pub fn get_grapheme_len(name: &String) -> usize {
@@ -422,3 +791,115 @@ pub fn test_length() {
     assert_eq!(get_grapheme_len(&string_5), 2);
     assert_eq!(get_grapheme_len(&string_6), 1);
 }
+
+#[test]
+pub fn test_price_schedule() {
+    let mut schedule = PriceSchedule::default();
+    assert_eq!(schedule.get_usd_price(1), 750_000_000);
+    assert_eq!(schedule.get_usd_price(4), 160_000_000);
+    assert_eq!(schedule.get_usd_price(12), 20_000_000);
+
+    schedule.tiers[3].override_price_usd = Some(100_000_000);
+    assert_eq!(schedule.get_usd_price(4), 100_000_000);
+    assert!(schedule.check().is_ok());
+
+    schedule.tiers[0].length = 2;
+    assert!(schedule.check().is_err());
+
+    schedule.volume_discounts = vec![
+        crate::state::VolumeDiscount {
+            min_names: 3,
+            discount_percent: 5,
+        },
+        crate::state::VolumeDiscount {
+            min_names: 5,
+            discount_percent: 10,
+        },
+    ];
+    assert_eq!(schedule.get_volume_discount_percent(2), 0);
+    assert_eq!(schedule.get_volume_discount_percent(4), 5);
+    assert_eq!(schedule.get_volume_discount_percent(10), 10);
+}
+
+#[test]
+pub fn test_premium_proof() {
+    let mut names = (0..5u8)
+        .map(|i| hashv(&[&[i]]).to_bytes())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    let leaves = names
+        .iter()
+        .map(|n| premium_leaf(n, 1_000_000_000))
+        .collect::<Vec<_>>();
+
+    // ((l0, l1), (l2, l3)), l4
+    let n01 = premium_node(&leaves[0], &leaves[1]);
+    let n23 = premium_node(&leaves[2], &leaves[3]);
+    let n0123 = premium_node(&n01, &n23);
+    let schedule = PriceSchedule {
+        premium_root: premium_node(&n0123, &leaves[4]),
+        premium_count: 5,
+        ..PriceSchedule::default()
+    };
+
+    let proof = |index: usize, siblings: Vec<[u8; 32]>| PremiumProof {
+        hashed_name: names[index],
+        price_usd: 1_000_000_000,
+        index: index as u32,
+        siblings,
+    };
+    let p2 = proof(2, vec![leaves[3], n01, leaves[4]]);
+    let p3 = proof(3, vec![leaves[2], n01, leaves[4]]);
+    let p4 = proof(4, vec![n0123]);
+
+    assert_eq!(
+        get_premium_price(&schedule, &names[2], std::slice::from_ref(&p2)).unwrap(),
+        Some(1_000_000_000)
+    );
+    assert_eq!(
+        get_premium_price(&schedule, &names[4], &[p4]).unwrap(),
+        Some(1_000_000_000)
+    );
+
+    // A name between two leaves is not premium
+    let mut other = names[2];
+    other[31] += 1;
+    assert_eq!(
+        get_premium_price(&schedule, &other, &[p2.clone(), p3]).unwrap(),
+        None
+    );
+
+    // A premium name cannot be bought without its leaf
+    assert!(get_premium_price(&schedule, &names[2], &[]).is_err());
+    let mut forged = p2;
+    forged.price_usd = 1;
+    assert!(get_premium_price(&schedule, &names[2], &[forged]).is_err());
+}
+
+#[test]
+pub fn test_release_premium() {
+    use crate::state::{PremiumDecay, ReleasePremium};
+
+    let linear = ReleasePremium {
+        start_premium_usd: 1_000_000_000,
+        window: 1000,
+        decay: PremiumDecay::Linear,
+    };
+    assert_eq!(linear.get_premium(0), 1_000_000_000);
+    assert_eq!(linear.get_premium(250), 750_000_000);
+    assert_eq!(linear.get_premium(1000), 0);
+
+    let exponential = ReleasePremium {
+        start_premium_usd: 1_000_000_000,
+        window: 1000,
+        decay: PremiumDecay::Exponential { half_life: 100 },
+    };
+    assert_eq!(exponential.get_premium(100), 500_000_000);
+    assert_eq!(exponential.get_premium(150), 375_000_000);
+    assert_eq!(exponential.get_premium(200), 250_000_000);
+    assert_eq!(
+        exponential.get_premium(999),
+        1_953_125 - 1_953_125 * 99 / 200
+    );
+    assert_eq!(exponential.get_premium(1000), 0);
+}
@@ -1,15 +1,27 @@
-use std::str::FromStr;
+#![cfg(feature = "test-bpf")]
 
 use borsh::BorshSerialize;
 use name_auctioning::{
-    instructions::{create, create_reverse, end_auction, init, resell},
-    processor::{
-        AUCTION_PROGRAM_ID, BONFIDA_FIDA_VAULT, FIDA_MINT, ROOT_DOMAIN_ACCOUNT, TOKEN_MINT,
+    auction::{Auction, BidRefund, SealedBid},
+    instructions::{
+        accept_bid, approve_proposal, claim, commit_bid, create, create_proposal, create_reverse,
+        create_v2, create_v2_batch, end_auction, init, migrate_central_state, place_bid,
+        reclaim_expired, refund_sealed_bid, renew, resell, reveal_bid, reveal_reserve,
+        set_admin_set, set_pause, set_payment_mint, set_price_schedule, update_blocklist,
+        update_config, withdraw_refund,
+    },
+    oracle::TestPrice,
+    processor::{FIDA_MINT, ROOT_DOMAIN_ACCOUNT, TOKEN_MINT, USDC_MINT},
+    state::{
+        AdminAction, BlocklistPage, CentralState, PaymentMint, PriceSchedule, ProgramConfig,
+        Proposal, ReferralStats, Registration, UsedVoucher, VolumeDiscount, Voucher,
+        CENTRAL_STATE_VERSION, GRACE_PERIOD, PAUSE_ALL, PAUSE_CLAIM, PAUSE_CREATE,
+        PAUSE_CREATE_REVERSE, PAUSE_PLACE_BID, REGISTRATION_PERIOD,
     },
 };
 use solana_program::{
-    hash::hashv, instruction::Instruction, program_option::COption, program_pack::Pack,
-    pubkey::Pubkey, rent::Rent,
+    clock::Clock, ed25519_program, hash::hashv, instruction::Instruction, program_option::COption,
+    program_pack::Pack, pubkey::Pubkey, rent::Rent,
 };
 use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
 use solana_sdk::{
@@ -18,7 +30,6 @@ use solana_sdk::{
     system_instruction,
     transaction::Transaction,
 };
-use spl_auction::{processor::EXCLUSIVE_AUCTION_AUTHORITY, PREFIX};
 use spl_name_service::{
     instruction::NameRegistryInstruction,
     state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX},
@@ -28,13 +39,12 @@ use spl_token::state::Mint;
 
 #[tokio::test]
 async fn test() {
-    let program_id = Pubkey::from_str(EXCLUSIVE_AUCTION_AUTHORITY).unwrap();
+    let program_id = Pubkey::new_unique();
     let mint_authority = Keypair::new();
     let bonfida_vault_owner = Keypair::new();
+    let config = test_config();
     let mut program_test = ProgramTest::new("name_auctioning", program_id, None);
-    let auction_program_id = AUCTION_PROGRAM_ID;
     program_test.add_program("spl_name_service", spl_name_service::id(), None);
-    program_test.add_program("spl_auction", auction_program_id, None);
 
     let mut mint_data = vec![0u8; Mint::LEN];
     Mint {
@@ -71,7 +81,7 @@ async fn test() {
     .pack_into_slice(&mut vault_data);
 
     program_test.add_account(
-        BONFIDA_FIDA_VAULT,
+        Pubkey::new(&config.bonfida_fida_vault),
         Account {
             lamports: 1_000_000,
             data: vault_data,
@@ -102,17 +112,6 @@ async fn test() {
         },
     );
 
-    program_test.add_account(
-        derived_central_state_key,
-        Account {
-            lamports: 1_000_000,
-            data: vec![state_nonce],
-            owner: program_id,
-            executable: false,
-            ..Account::default()
-        },
-    );
-
     let mut ctx = program_test.start_with_context().await;
 
     let init_instruction = init(
@@ -120,11 +119,25 @@ async fn test() {
         derived_central_state_key,
         ctx.payer.pubkey(),
         state_nonce,
+        ctx.payer.pubkey(),
+        ctx.payer.pubkey(),
+        ctx.payer.pubkey(),
+        config.clone(),
     );
 
-    sign_send_instruction(&mut ctx, init_instruction, vec![])
+    sign_send_instruction(&mut ctx, init_instruction.clone(), vec![])
+        .await
+        .unwrap();
+
+    // The central state cannot be initialized twice
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
         .await
         .unwrap();
+    assert!(sign_send_instruction(&mut ctx, init_instruction, vec![])
+        .await
+        .is_err());
 
     let root_name_account_key = ROOT_DOMAIN_ACCOUNT;
 
@@ -154,30 +167,7 @@ async fn test() {
         Some(&root_name_account_key),
     );
 
-    let auction_seeds = &[
-        PREFIX.as_bytes(),
-        &auction_program_id.to_bytes(),
-        name_account.as_ref(),
-    ];
-    let (auction_account, _) = Pubkey::find_program_address(auction_seeds, &auction_program_id);
-
-    // let rent = ctx.banks_client.get_rent().await.unwrap();
-
-    // let allocate_auction_account_instruction = system_instruction::create_account(
-    //     &ctx.payer.pubkey(),
-    //     &auction_account.pubkey(),
-    //     rent.minimum_balance(BASE_AUCTION_DATA_SIZE),
-    //     BASE_AUCTION_DATA_SIZE as u64,
-    //     &&auction_program_id,
-    // );
-
-    // sign_send_instruction(
-    //     &mut ctx,
-    //     allocate_auction_account_instruction,
-    //     vec![&auction_account],
-    // )
-    // .await
-    // .unwrap();
+    let (auction_account, _) = Auction::find_key(&program_id, &name_account);
 
     println!("{:?}", key.len());
     let (derived_state_key, _) =
@@ -205,7 +195,6 @@ async fn test() {
 
     let create_naming_auction_instruction = create(
         program_id,
-        auction_program_id,
         root_name_account_key,
         name_account,
         reverse_lookup_account_key,
@@ -214,16 +203,152 @@ async fn test() {
         derived_state_key,
         ctx.payer.pubkey(),
         TOKEN_MINT,
+        Pubkey::new(&config.pyth_fida_price_acc),
+        Pubkey::new(&config.pyth_fida_fallback_price_acc),
         test_name.to_owned(),
+        false,
+        false,
+    );
+
+    // Create is rejected while paused
+    let admin = ctx.payer.pubkey();
+    set_admins(&mut ctx, &program_id, vec![admin], 1).await;
+    let proposal = propose(
+        &mut ctx,
+        &program_id,
+        0,
+        AdminAction::SetPause {
+            flags: PAUSE_CREATE,
+            paused: true,
+        },
+        vec![],
+    )
+    .await;
+    let pause_instruction = set_pause(
+        program_id,
+        derived_central_state_key,
+        proposal,
+        PAUSE_CREATE,
+        true,
+    );
+    sign_send_instruction(&mut ctx, pause_instruction, vec![])
+        .await
+        .unwrap();
+    assert!(
+        sign_send_instruction(&mut ctx, create_naming_auction_instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+
+    let proposal = propose(
+        &mut ctx,
+        &program_id,
+        1,
+        AdminAction::SetPause {
+            flags: PAUSE_CREATE,
+            paused: false,
+        },
+        vec![],
+    )
+    .await;
+    let unpause_instruction = set_pause(
+        program_id,
+        derived_central_state_key,
+        proposal,
+        PAUSE_CREATE,
+        false,
+    );
+    sign_send_instruction(&mut ctx, unpause_instruction, vec![])
+        .await
+        .unwrap();
+
+    // Create is rejected when the FIDA price is too uncertain
+    let fida_price_account = Pubkey::new(&config.pyth_fida_price_acc);
+    set_test_price(
+        &mut ctx,
+        &program_id,
+        &fida_price_account,
+        20_000_000,
+        1_000_000,
+        -8,
+    )
+    .await;
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+    assert!(
+        sign_send_instruction(&mut ctx, create_naming_auction_instruction.clone(), vec![])
+            .await
+            .is_err()
     );
 
+    set_test_price(
+        &mut ctx,
+        &program_id,
+        &fida_price_account,
+        20_000_000,
+        10_000,
+        -8,
+    )
+    .await;
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
     sign_send_instruction(&mut ctx, create_naming_auction_instruction, vec![])
         .await
         .unwrap();
 
+    // Bid on the auction
+    let bidder_token_account = Keypair::new();
+    create_token_account(&mut ctx, &TOKEN_MINT, &bidder_token_account).await;
+    let mint_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &TOKEN_MINT,
+        &bidder_token_account.pubkey(),
+        &mint_authority.pubkey(),
+        &[],
+        10_000_000_000,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, mint_instruction, vec![&mint_authority])
+        .await
+        .unwrap();
+
+    let place_bid_instruction = place_bid(
+        program_id,
+        name_account,
+        ctx.payer.pubkey(),
+        bidder_token_account.pubkey(),
+        derived_central_state_key,
+        None,
+        1_000_000_000,
+    );
+    sign_send_instruction(&mut ctx, place_bid_instruction, vec![])
+        .await
+        .unwrap();
+
+    // A lower bid does not outbid the top bid
+    let place_bid_instruction = place_bid(
+        program_id,
+        name_account,
+        ctx.payer.pubkey(),
+        bidder_token_account.pubkey(),
+        derived_central_state_key,
+        Some(ctx.payer.pubkey()),
+        1_005_000_000,
+    );
+    assert!(
+        sign_send_instruction(&mut ctx, place_bid_instruction, vec![])
+            .await
+            .is_err()
+    );
+
     // let reset_auction_instruction = reset_auction(
     //     program_id,
-    //     auction_program_id,
     //     ctx.payer.pubkey(),
     //     auction_account,
     //     name_account,
@@ -239,10 +364,9 @@ async fn test_resell() {
     let program_id = Pubkey::new_unique();
     let mint_authority = Keypair::new();
     let bonfida_vault_owner = Keypair::new();
+    let config = test_config();
     let mut program_test = ProgramTest::new("name_auctioning", program_id, None);
-    let auction_program_id = AUCTION_PROGRAM_ID;
     program_test.add_program("spl_name_service", spl_name_service::id(), None);
-    program_test.add_program("spl_auction", auction_program_id, None);
 
     let mut mint_data = vec![0u8; Mint::LEN];
     Mint {
@@ -279,7 +403,7 @@ async fn test_resell() {
     .pack_into_slice(&mut vault_data);
 
     program_test.add_account(
-        BONFIDA_FIDA_VAULT,
+        Pubkey::new(&config.bonfida_fida_vault),
         Account {
             lamports: 1_000_000,
             data: vault_data,
@@ -292,17 +416,6 @@ async fn test_resell() {
     let (derived_central_state_key, state_nonce) =
         Pubkey::find_program_address(&[&program_id.to_bytes()], &program_id);
 
-    program_test.add_account(
-        derived_central_state_key,
-        Account {
-            lamports: 1_000_000,
-            data: vec![state_nonce],
-            owner: program_id,
-            executable: false,
-            ..Account::default()
-        },
-    );
-
     let temp_owner = Keypair::new();
 
     let root_domain_data = spl_name_service::state::NameRecordHeader {
@@ -332,6 +445,10 @@ async fn test_resell() {
         derived_central_state_key,
         ctx.payer.pubkey(),
         state_nonce,
+        ctx.payer.pubkey(),
+        ctx.payer.pubkey(),
+        ctx.payer.pubkey(),
+        config.clone(),
     );
 
     sign_send_instruction(&mut ctx, init_instruction, vec![])
@@ -353,12 +470,7 @@ async fn test_resell() {
             .as_ref()
             .to_vec();
 
-    let auction_seeds = &[
-        PREFIX.as_bytes(),
-        &auction_program_id.to_bytes(),
-        name_account_key.as_ref(),
-    ];
-    let (auction_account, _) = Pubkey::find_program_address(auction_seeds, &auction_program_id);
+    let (auction_account, _) = Auction::find_key(&program_id, &name_account_key);
 
     let (derived_state_key, _) =
         Pubkey::find_program_address(&[&name_account_key.to_bytes()], &program_id);
@@ -497,7 +609,6 @@ async fn test_resell() {
 
     let resell_naming_auction_instruction = resell(
         program_id,
-        auction_program_id,
         root_name_account_key,
         name_account_key,
         ctx.payer.pubkey(),
@@ -508,10 +619,15 @@ async fn test_resell() {
         ctx.payer.pubkey(),
         derived_reselling_state_key,
         destination_account.pubkey(),
+        TOKEN_MINT,
         name.to_owned(),
         10,
         10,
         None,
+        Some(300),
+        None,
+        false,
+        None,
     );
 
     sign_send_instruction(&mut ctx, resell_naming_auction_instruction, vec![])
@@ -527,11 +643,13 @@ async fn test_resell() {
         auction_account,
         derived_central_state_key,
         derived_state_key,
-        auction_program_id,
         ctx.payer.pubkey(),
         derived_reselling_state_key,
         destination_account.pubkey(),
+        Pubkey::new(&config.bonfida_sol_vault),
         name.to_owned(),
+        None,
+        Pubkey::new(&config.bonfida_fida_vault),
     );
 
     sign_send_instruction(&mut ctx, cancel_auction_instruction, vec![])
@@ -539,19 +657,1964 @@ async fn test_resell() {
         .unwrap();
 }
 
-// Utils
-pub async fn sign_send_instruction(
-    ctx: &mut ProgramTestContext,
-    instruction: Instruction,
-    signers: Vec<&Keypair>,
-) -> Result<(), BanksClientError> {
-    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&ctx.payer.pubkey()));
-    let mut payer_signers = vec![&ctx.payer];
-    for s in signers {
-        payer_signers.push(s);
-    }
-    transaction.partial_sign(&payer_signers, ctx.last_blockhash);
-    ctx.banks_client.process_transaction(transaction).await
+#[tokio::test]
+async fn test_admin_proposals() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let second_admin = Keypair::new();
+    let admin = env.ctx.payer.pubkey();
+    set_admins(
+        &mut env.ctx,
+        &program_id,
+        vec![admin, second_admin.pubkey()],
+        2,
+    )
+    .await;
+
+    // A proposal approved by its proposer only is under the threshold
+    let pause = AdminAction::SetPause {
+        flags: PAUSE_CREATE,
+        paused: true,
+    };
+    let proposal = propose(&mut env.ctx, &program_id, 0, pause, vec![]).await;
+    let pause_instruction = set_pause(program_id, central_state, proposal, PAUSE_CREATE, true);
+    assert!(
+        sign_send_instruction(&mut env.ctx, pause_instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+
+    // The approved proposal cannot be used for another action
+    sign_send_instruction(
+        &mut env.ctx,
+        approve_proposal(program_id, second_admin.pubkey(), proposal),
+        vec![&second_admin],
+    )
+    .await
+    .unwrap();
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        set_pause(program_id, central_state, proposal, PAUSE_CLAIM, true),
+        vec![],
+    )
+    .await
+    .is_err());
+    let mut config = env.config.clone();
+    config.reveal_length = 3_600;
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        update_config(program_id, central_state, proposal, config.clone()),
+        vec![],
+    )
+    .await
+    .is_err());
+
+    refresh_blockhash(&mut env.ctx).await;
+    sign_send_instruction(&mut env.ctx, pause_instruction.clone(), vec![])
+        .await
+        .unwrap();
+
+    // Proposals are executed once
+    refresh_blockhash(&mut env.ctx).await;
+    assert!(
+        sign_send_instruction(&mut env.ctx, pause_instruction, vec![])
+            .await
+            .is_err()
+    );
+
+    // Keys outside of the admin set cannot approve
+    let outsider = Keypair::new();
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        1,
+        AdminAction::UpdateConfig(Box::new(config.clone())),
+        vec![],
+    )
+    .await;
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        approve_proposal(program_id, outsider.pubkey(), proposal),
+        vec![&outsider],
+    )
+    .await
+    .is_err());
+    let update_config_instruction = update_config(program_id, central_state, proposal, config);
+    assert!(
+        sign_send_instruction(&mut env.ctx, update_config_instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+    sign_send_instruction(
+        &mut env.ctx,
+        approve_proposal(program_id, second_admin.pubkey(), proposal),
+        vec![&second_admin],
+    )
+    .await
+    .unwrap();
+    refresh_blockhash(&mut env.ctx).await;
+    sign_send_instruction(&mut env.ctx, update_config_instruction, vec![])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_pause_flags() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let admin = env.ctx.payer.pubkey();
+    set_admins(&mut env.ctx, &program_id, vec![admin], 1).await;
+
+    let flags = PAUSE_CREATE_REVERSE | PAUSE_PLACE_BID;
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        0,
+        AdminAction::SetPause {
+            flags,
+            paused: true,
+        },
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        set_pause(program_id, central_state, proposal, flags, true),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    // Instructions are only rejected when their own flag is set
+    let instruction = create_auction(&env, "paused", false, false);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let instruction = create_reverse(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        name_accounts(&env, "reverse").reverse_lookup,
+        central_state,
+        admin,
+        "reverse".to_owned(),
+        None,
+        None,
+    );
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+
+    let bidder_tokens = funded_token_account(&mut env, &TOKEN_MINT, &admin, 10_000_000_000).await;
+    let name_account = name_accounts(&env, "paused").name;
+    let bid_instruction = place_bid(
+        program_id,
+        name_account,
+        admin,
+        bidder_tokens,
+        central_state,
+        None,
+        1_000_000_000,
+    );
+    assert!(
+        sign_send_instruction(&mut env.ctx, bid_instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        1,
+        AdminAction::SetPause {
+            flags,
+            paused: false,
+        },
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        set_pause(program_id, central_state, proposal, flags, false),
+        vec![],
+    )
+    .await
+    .unwrap();
+    refresh_blockhash(&mut env.ctx).await;
+    sign_send_instruction(&mut env.ctx, bid_instruction, vec![])
+        .await
+        .unwrap();
+
+    // PAUSE_ALL rejects every pausable instruction
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        2,
+        AdminAction::SetPause {
+            flags: PAUSE_ALL,
+            paused: true,
+        },
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        set_pause(program_id, central_state, proposal, PAUSE_ALL, true),
+        vec![],
+    )
+    .await
+    .unwrap();
+    let instruction = create_auction(&env, "another", false, false);
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_blocklist() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let admin = env.ctx.payer.pubkey();
+    set_admins(&mut env.ctx, &program_id, vec![admin], 1).await;
+
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + "blocked").as_bytes()]).to_bytes();
+    let page = BlocklistPage::page_of(&hashed_name);
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        0,
+        AdminAction::UpdateBlocklist {
+            page,
+            add: vec![hashed_name],
+            remove: vec![],
+        },
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        update_blocklist(
+            program_id,
+            central_state,
+            admin,
+            proposal,
+            page,
+            vec![hashed_name],
+            vec![],
+        ),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    // Blocked names cannot be auctioned nor bought directly
+    let instruction = create_auction(&env, "blocked", false, false);
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+    let usdc_tokens = funded_token_account(&mut env, &USDC_MINT, &admin, 1_000_000_000).await;
+    let vault = Pubkey::new(&env.config.bonfida_usdc_vault);
+    let instruction = create_v2_direct(
+        &env,
+        "blocked",
+        usdc_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        None,
+    );
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+
+    // Other names are not affected
+    let instruction = create_auction(&env, "unblocked", false, false);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let instruction = create_v2_direct(
+        &env,
+        "allowed",
+        usdc_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_payment_mints() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let admin = env.ctx.payer.pubkey();
+    set_admins(&mut env.ctx, &program_id, vec![admin], 1).await;
+
+    // A mint priced through its own feed, worth 2 USD
+    let mint = Pubkey::new_unique();
+    let vault = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+    let mint_authority = env.mint_authority.pubkey();
+    env.ctx
+        .set_account(&mint, &mint_account(&mint_authority).into());
+    env.ctx
+        .set_account(&vault, &token_account(mint, Pubkey::new_unique()).into());
+    set_test_price(
+        &mut env.ctx,
+        &program_id,
+        &price_account,
+        200_000_000,
+        100_000,
+        -8,
+    )
+    .await;
+
+    let payment_mint = PaymentMint {
+        mint: mint.to_bytes(),
+        vault: vault.to_bytes(),
+        pyth_price_acc: Some(price_account.to_bytes()),
+        enabled: true,
+    };
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        0,
+        AdminAction::SetPaymentMint(payment_mint.clone()),
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        set_payment_mint(program_id, central_state, admin, proposal, payment_mint),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let buyer_tokens = funded_token_account(&mut env, &mint, &admin, 1_000_000_000).await;
+    let instruction = create_v2_direct(
+        &env,
+        "whitelisted",
+        buyer_tokens,
+        mint,
+        vault,
+        price_account,
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let paid = token_balance(&mut env.ctx, &vault).await;
+    assert!(paid > 0);
+    assert_eq!(
+        token_balance(&mut env.ctx, &buyer_tokens).await,
+        1_000_000_000 - paid
+    );
+
+    // Mints which were not whitelisted are rejected
+    let other_mint = Pubkey::new_unique();
+    let other_vault = Pubkey::new_unique();
+    env.ctx
+        .set_account(&other_mint, &mint_account(&mint_authority).into());
+    env.ctx.set_account(
+        &other_vault,
+        &token_account(other_mint, Pubkey::new_unique()).into(),
+    );
+    let other_tokens = funded_token_account(&mut env, &other_mint, &admin, 1_000_000_000).await;
+    let instruction = create_v2_direct(
+        &env,
+        "notwhitelisted",
+        other_tokens,
+        other_mint,
+        other_vault,
+        price_account,
+        None,
+    );
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_registrations() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let owner = env.ctx.payer.pubkey();
+    let vault = Pubkey::new(&env.config.bonfida_usdc_vault);
+    let owner_tokens = funded_token_account(&mut env, &USDC_MINT, &owner, 1_000_000_000).await;
+    let other = Keypair::new();
+    let other_tokens =
+        funded_token_account(&mut env, &USDC_MINT, &other.pubkey(), 1_000_000_000).await;
+
+    let name = "renewable";
+    let name_account = name_accounts(&env, name).name;
+    let instruction = create_v2_direct(
+        &env,
+        name,
+        owner_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let expires_at = registration_expiry(&mut env, &name_account).await;
+
+    // Anyone can renew a name before it expires
+    let renew_instruction = |buyer: Pubkey, buyer_tokens: Pubkey| {
+        renew(
+            program_id,
+            name_account,
+            central_state,
+            buyer,
+            buyer_tokens,
+            vault,
+            USDC_MINT,
+            Pubkey::default(),
+            name.to_owned(),
+            1,
+        )
+    };
+    let balance = token_balance(&mut env.ctx, &other_tokens).await;
+    sign_send_instruction(
+        &mut env.ctx,
+        renew_instruction(other.pubkey(), other_tokens),
+        vec![&other],
+    )
+    .await
+    .unwrap();
+    assert!(token_balance(&mut env.ctx, &other_tokens).await < balance);
+    let expires_at = expires_at + REGISTRATION_PERIOD;
+    assert_eq!(
+        registration_expiry(&mut env, &name_account).await,
+        expires_at
+    );
+
+    // Only the owner can renew during the grace period, the name cannot be reclaimed yet
+    warp_to_timestamp(&mut env.ctx, expires_at + 1).await;
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        renew_instruction(other.pubkey(), other_tokens),
+        vec![&other],
+    )
+    .await
+    .is_err());
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        reclaim_expired(program_id, name_account, central_state),
+        vec![],
+    )
+    .await
+    .is_err());
+    sign_send_instruction(&mut env.ctx, renew_instruction(owner, owner_tokens), vec![])
+        .await
+        .unwrap();
+    let expires_at = expires_at + REGISTRATION_PERIOD;
+    assert_eq!(
+        registration_expiry(&mut env, &name_account).await,
+        expires_at
+    );
+
+    // Past the grace period the name can no longer be renewed and goes back to the central state
+    warp_to_timestamp(&mut env.ctx, expires_at + GRACE_PERIOD).await;
+    assert!(
+        sign_send_instruction(&mut env.ctx, renew_instruction(owner, owner_tokens), vec![],)
+            .await
+            .is_err()
+    );
+    sign_send_instruction(
+        &mut env.ctx,
+        reclaim_expired(program_id, name_account, central_state),
+        vec![],
+    )
+    .await
+    .unwrap();
+    assert_eq!(name_owner(&mut env.ctx, &name_account).await, central_state);
+}
+
+#[tokio::test]
+async fn test_vouchers() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let buyer = env.ctx.payer.pubkey();
+    let vault = Pubkey::new(&env.config.bonfida_usdc_vault);
+    let buyer_tokens = funded_token_account(&mut env, &USDC_MINT, &buyer, 1_000_000_000).await;
+    let clock: Clock = env.ctx.banks_client.get_sysvar().await.unwrap();
+    let hashed_name = |name: &str| hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()]).to_bytes();
+
+    // Full price of a name of the same length
+    let instruction = create_v2_direct(
+        &env,
+        "fullprice",
+        buyer_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let full_price = token_balance(&mut env.ctx, &vault).await;
+
+    // The admin signs a voucher halving the price of a given name
+    let voucher = Voucher {
+        discount_percent: 50,
+        hashed_name: Some(hashed_name("halfprice")),
+        expires_at: clock.unix_timestamp + 3_600,
+        nonce: 1,
+    };
+    let instructions = voucher_instructions(&env, "halfprice", &voucher, buyer_tokens);
+    sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut env.ctx, &vault).await,
+        full_price + full_price / 2
+    );
+    let used_voucher = env
+        .ctx
+        .banks_client
+        .get_account(UsedVoucher::find_key(&program_id, 1).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let used_voucher = UsedVoucher::unpack_from_slice(&used_voucher.data).unwrap();
+    assert_eq!(used_voucher.used_by, buyer.to_bytes());
+
+    // Vouchers are only valid for their name, before their expiry and once
+    let instructions = voucher_instructions(&env, "othername", &voucher, buyer_tokens);
+    assert!(sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .is_err());
+    let expired = Voucher {
+        hashed_name: None,
+        expires_at: clock.unix_timestamp - 1,
+        nonce: 2,
+        ..voucher
+    };
+    let instructions = voucher_instructions(&env, "expired", &expired, buyer_tokens);
+    assert!(sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .is_err());
+    let any_name = Voucher {
+        expires_at: clock.unix_timestamp + 3_600,
+        ..expired
+    };
+    let instructions = voucher_instructions(&env, "firstuse", &any_name, buyer_tokens);
+    sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .unwrap();
+    let instructions = voucher_instructions(&env, "replayed", &any_name, buyer_tokens);
+    assert!(sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .is_err());
+
+    // The voucher signature has to be verified in the previous instruction
+    let any_name = Voucher {
+        nonce: 3,
+        ..any_name
+    };
+    let mut instructions = voucher_instructions(&env, "unverified", &any_name, buyer_tokens);
+    instructions.remove(0);
+    assert!(sign_send_instructions(&mut env.ctx, instructions, vec![])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_batch_registration() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let buyer = env.ctx.payer.pubkey();
+    let vault = Pubkey::new(&env.config.bonfida_usdc_vault);
+    let buyer_tokens = funded_token_account(&mut env, &USDC_MINT, &buyer, 1_000_000_000).await;
+    set_admins(&mut env.ctx, &program_id, vec![buyer], 1).await;
+
+    // 10% off from three names
+    let schedule = PriceSchedule {
+        volume_discounts: vec![VolumeDiscount {
+            min_names: 3,
+            discount_percent: 10,
+        }],
+        ..PriceSchedule::default()
+    };
+    let proposal = propose(
+        &mut env.ctx,
+        &program_id,
+        0,
+        AdminAction::SetPriceSchedule(schedule.clone()),
+        vec![],
+    )
+    .await;
+    sign_send_instruction(
+        &mut env.ctx,
+        set_price_schedule(program_id, central_state, buyer, proposal, schedule.clone()),
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let batch_instruction = |names: &[&str]| {
+        create_v2_batch(
+            program_id,
+            ROOT_DOMAIN_ACCOUNT,
+            central_state,
+            buyer,
+            buyer_tokens,
+            vault,
+            USDC_MINT,
+            Pubkey::default(),
+            names.iter().map(|n| (n.to_string(), 1_000)).collect(),
+            vec![],
+            None,
+        )
+    };
+
+    // Duplicate names are rejected
+    assert!(sign_send_instruction(
+        &mut env.ctx,
+        batch_instruction(&["twice", "other", "twice"]),
+        vec![],
+    )
+    .await
+    .is_err());
+
+    // The names are paid together, with the volume discount
+    let names = ["abcd", "batched", "together"];
+    sign_send_instruction(&mut env.ctx, batch_instruction(&names), vec![])
+        .await
+        .unwrap();
+    let full_price = names
+        .iter()
+        .map(|n| schedule.get_usd_price(n.len()))
+        .sum::<u64>();
+    assert_eq!(
+        token_balance(&mut env.ctx, &vault).await,
+        full_price * 90 / 100
+    );
+    assert_eq!(
+        token_balance(&mut env.ctx, &buyer_tokens).await,
+        1_000_000_000 - full_price * 90 / 100
+    );
+    for name in names {
+        let name_account = name_accounts(&env, name).name;
+        assert_eq!(name_owner(&mut env.ctx, &name_account).await, buyer);
+    }
+}
+
+#[tokio::test]
+async fn test_referrals() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let buyer = env.ctx.payer.pubkey();
+    let vault = Pubkey::new(&env.config.bonfida_usdc_vault);
+    let buyer_tokens = funded_token_account(&mut env, &USDC_MINT, &buyer, 1_000_000_000).await;
+    let referrer = Keypair::new();
+    let referrer_tokens = funded_token_account(&mut env, &USDC_MINT, &referrer.pubkey(), 0).await;
+
+    // The referrer gets its share of the price and the referral is recorded
+    let instruction = create_v2_direct(
+        &env,
+        "referred",
+        buyer_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        Some(referrer_tokens),
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let share = token_balance(&mut env.ctx, &referrer_tokens).await;
+    let vault_amount = token_balance(&mut env.ctx, &vault).await;
+    assert_eq!(
+        share,
+        (share + vault_amount) * env.config.referral_share_bps / 10_000
+    );
+    let (stats_key, _) = ReferralStats::find_key(&program_id, &referrer_tokens);
+    let stats = env
+        .ctx
+        .banks_client
+        .get_account(stats_key)
+        .await
+        .unwrap()
+        .unwrap();
+    let stats = ReferralStats::unpack_from_slice(&stats.data).unwrap();
+    assert_eq!(stats.referrals, 1);
+    assert_eq!(stats.total_paid, share);
+
+    // Buyers cannot refer themselves
+    let self_referral_tokens = funded_token_account(&mut env, &USDC_MINT, &buyer, 0).await;
+    let instruction = create_v2_direct(
+        &env,
+        "selfreferred",
+        buyer_tokens,
+        USDC_MINT,
+        vault,
+        Pubkey::default(),
+        Some(self_referral_tokens),
+    );
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_auction_settlement() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let winner = env.ctx.payer.pubkey();
+    let accounts = name_accounts(&env, "settled");
+    let instruction = create_auction(&env, "settled", false, false);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let minimum_price = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .minimum_price;
+
+    // An outbid bidder cannot block the next bid by closing its token account
+    let outbid_bidder = Keypair::new();
+    let instruction = system_instruction::transfer(&winner, &outbid_bidder.pubkey(), 100_000_000);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let outbid_tokens = funded_token_account(
+        &mut env,
+        &TOKEN_MINT,
+        &outbid_bidder.pubkey(),
+        minimum_price,
+    )
+    .await;
+    let instructions = vec![
+        place_bid(
+            program_id,
+            accounts.name,
+            outbid_bidder.pubkey(),
+            outbid_tokens,
+            central_state,
+            None,
+            minimum_price,
+        ),
+        spl_token::instruction::close_account(
+            &spl_token::id(),
+            &outbid_tokens,
+            &outbid_bidder.pubkey(),
+            &outbid_bidder.pubkey(),
+            &[],
+        )
+        .unwrap(),
+    ];
+    sign_send_instructions(&mut env.ctx, instructions, vec![&outbid_bidder])
+        .await
+        .unwrap();
+
+    let winning_bid = 2 * minimum_price;
+    let winner_tokens = funded_token_account(&mut env, &TOKEN_MINT, &winner, winning_bid).await;
+    let instruction = place_bid(
+        program_id,
+        accounts.name,
+        winner,
+        winner_tokens,
+        central_state,
+        Some(outbid_bidder.pubkey()),
+        winning_bid,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+
+    // The outbid bid is credited, then withdrawn to another token account
+    let (bid_refund, _) =
+        BidRefund::find_key(&program_id, &accounts.auction, &outbid_bidder.pubkey());
+    let account = env
+        .ctx
+        .banks_client
+        .get_account(bid_refund)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        BidRefund::unpack_from_slice(&account.data).unwrap().amount,
+        minimum_price
+    );
+    let refund_tokens =
+        funded_token_account(&mut env, &TOKEN_MINT, &outbid_bidder.pubkey(), 0).await;
+    let instruction = withdraw_refund(
+        program_id,
+        accounts.name,
+        outbid_bidder.pubkey(),
+        refund_tokens,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&outbid_bidder])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut env.ctx, &refund_tokens).await,
+        minimum_price
+    );
+    assert!(env
+        .ctx
+        .banks_client
+        .get_account(bid_refund)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The winner gets the name and the winning bid is paid out of the pot
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + "settled").as_bytes()]).to_bytes();
+    let vault = Pubkey::new(&env.config.bonfida_fida_vault);
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let instruction = claim(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.auction,
+        accounts.state,
+        reselling_state,
+        central_state,
+        winner,
+        vault,
+        winner,
+        1_000,
+        hashed_name,
+        vault,
+        Pubkey::default(),
+        winner,
+        None,
+    );
+    assert!(
+        sign_send_instruction(&mut env.ctx, instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+
+    let end_auction_at = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .end_auction_at;
+    warp_to_timestamp(&mut env.ctx, end_auction_at).await;
+    sign_send_instruction(&mut env.ctx, instruction.clone(), vec![])
+        .await
+        .unwrap();
+    assert_eq!(name_owner(&mut env.ctx, &accounts.name).await, winner);
+    assert_eq!(token_balance(&mut env.ctx, &vault).await, winning_bid);
+    let (pot, _) = Auction::find_pot_key(&program_id, &accounts.name);
+    assert_eq!(token_balance(&mut env.ctx, &pot).await, 0);
+    assert!(auction_state(&mut env.ctx, &accounts.auction).await.settled);
+
+    refresh_blockhash(&mut env.ctx).await;
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_sealed_bid_auction() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let winner = env.ctx.payer.pubkey();
+    let accounts = name_accounts(&env, "sealed");
+    let instruction = create_auction(&env, "sealed", true, false);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let minimum_price = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .minimum_price;
+
+    // Both bidders deposit more than they bid to hide their bids
+    let deposit = 3 * minimum_price;
+    let loser = Keypair::new();
+    let instruction = system_instruction::transfer(&winner, &loser.pubkey(), 100_000_000);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let loser_tokens = funded_token_account(&mut env, &TOKEN_MINT, &loser.pubkey(), deposit).await;
+    let winner_tokens = funded_token_account(&mut env, &TOKEN_MINT, &winner, deposit).await;
+    let losing_bid = minimum_price;
+    let winning_bid = 2 * minimum_price;
+    let loser_salt = [1u8; 32];
+    let winner_salt = [2u8; 32];
+    let instruction = commit_bid(
+        program_id,
+        accounts.name,
+        loser.pubkey(),
+        loser_tokens,
+        central_state,
+        SealedBid::commitment(&loser.pubkey(), losing_bid, &loser_salt),
+        deposit,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&loser])
+        .await
+        .unwrap();
+    let instruction = commit_bid(
+        program_id,
+        accounts.name,
+        winner,
+        winner_tokens,
+        central_state,
+        SealedBid::commitment(&winner, winning_bid, &winner_salt),
+        deposit,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let (pot, _) = Auction::find_pot_key(&program_id, &accounts.name);
+    assert_eq!(token_balance(&mut env.ctx, &pot).await, 2 * deposit);
+
+    // Bids are revealed once the bidding period is over
+    let reveal_winner = reveal_bid(program_id, accounts.name, winner, winning_bid, winner_salt);
+    assert!(
+        sign_send_instruction(&mut env.ctx, reveal_winner.clone(), vec![])
+            .await
+            .is_err()
+    );
+    let auction = auction_state(&mut env.ctx, &accounts.auction).await;
+    warp_to_timestamp(&mut env.ctx, auction.end_auction_at).await;
+    sign_send_instruction(&mut env.ctx, reveal_winner, vec![])
+        .await
+        .unwrap();
+    let instruction = reveal_bid(
+        program_id,
+        accounts.name,
+        loser.pubkey(),
+        losing_bid,
+        loser_salt,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&loser])
+        .await
+        .unwrap();
+
+    // Deposits are refunded once the reveal period is over, the winner keeps its bid in the pot
+    let refund_loser = refund_sealed_bid(program_id, accounts.name, loser.pubkey(), winner);
+    assert!(
+        sign_send_instruction(&mut env.ctx, refund_loser.clone(), vec![])
+            .await
+            .is_err()
+    );
+    warp_to_timestamp(&mut env.ctx, auction.end_time()).await;
+    sign_send_instruction(&mut env.ctx, refund_loser, vec![])
+        .await
+        .unwrap();
+    let instruction = refund_sealed_bid(program_id, accounts.name, winner, winner);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    assert_eq!(
+        auction_state(&mut env.ctx, &accounts.auction)
+            .await
+            .open_commitments,
+        0
+    );
+
+    // The winner gets the name and the winning bid is paid out of the pot
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + "sealed").as_bytes()]).to_bytes();
+    let vault = Pubkey::new(&env.config.bonfida_fida_vault);
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let instruction = claim(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.auction,
+        accounts.state,
+        reselling_state,
+        central_state,
+        winner,
+        vault,
+        winner,
+        1_000,
+        hashed_name,
+        vault,
+        Pubkey::default(),
+        winner,
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    assert_eq!(name_owner(&mut env.ctx, &accounts.name).await, winner);
+    assert_eq!(token_balance(&mut env.ctx, &vault).await, winning_bid);
+    assert_eq!(
+        token_balance(&mut env.ctx, &pot).await,
+        2 * deposit - winning_bid
+    );
+
+    // Both bidders withdraw what they deposited above the winning bid
+    let instruction = withdraw_refund(
+        program_id,
+        accounts.name,
+        loser.pubkey(),
+        loser_tokens,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&loser])
+        .await
+        .unwrap();
+    let instruction = withdraw_refund(
+        program_id,
+        accounts.name,
+        winner,
+        winner_tokens,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut env.ctx, &loser_tokens).await, deposit);
+    assert_eq!(
+        token_balance(&mut env.ctx, &winner_tokens).await,
+        deposit - winning_bid
+    );
+    assert_eq!(token_balance(&mut env.ctx, &pot).await, 0);
+}
+
+#[tokio::test]
+async fn test_cancel_with_bids() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let seller = env.ctx.payer.pubkey();
+    let name = "cancelled";
+    let accounts = name_accounts(&env, name);
+    let usdc_tokens = funded_token_account(&mut env, &USDC_MINT, &seller, 1_000_000_000).await;
+    let instruction = create_v2_direct(
+        &env,
+        name,
+        usdc_tokens,
+        USDC_MINT,
+        Pubkey::new(&env.config.bonfida_usdc_vault),
+        Pubkey::default(),
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+
+    // The seller pays the penalty from its destination token account
+    let top_bid = 1_000_000_000;
+    let penalty = top_bid * env.config.cancellation_penalty_bps / 10_000;
+    let compensation = penalty * env.config.cancellation_compensation_bps / 10_000;
+    let seller_tokens = funded_token_account(&mut env, &TOKEN_MINT, &seller, penalty).await;
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let resell_instruction = resell(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        seller,
+        accounts.reverse_lookup,
+        accounts.auction,
+        central_state,
+        accounts.state,
+        seller,
+        reselling_state,
+        seller_tokens,
+        TOKEN_MINT,
+        name.to_owned(),
+        top_bid,
+        3_600,
+        None,
+        None,
+        None,
+        false,
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, resell_instruction.clone(), vec![])
+        .await
+        .unwrap();
+
+    let bidder = Keypair::new();
+    let instruction = system_instruction::transfer(&seller, &bidder.pubkey(), 100_000_000);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let bidder_tokens =
+        funded_token_account(&mut env, &TOKEN_MINT, &bidder.pubkey(), top_bid).await;
+    let bid_instruction = place_bid(
+        program_id,
+        accounts.name,
+        bidder.pubkey(),
+        bidder_tokens,
+        central_state,
+        None,
+        top_bid,
+    );
+    sign_send_instruction(&mut env.ctx, bid_instruction.clone(), vec![&bidder])
+        .await
+        .unwrap();
+
+    let fida_vault = Pubkey::new(&env.config.bonfida_fida_vault);
+    let cancel_instruction = end_auction(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.auction,
+        central_state,
+        accounts.state,
+        seller,
+        reselling_state,
+        seller_tokens,
+        Pubkey::new(&env.config.bonfida_sol_vault),
+        name.to_owned(),
+        Some(bidder.pubkey()),
+        fida_vault,
+    );
+    sign_send_instruction(&mut env.ctx, cancel_instruction.clone(), vec![])
+        .await
+        .unwrap();
+
+    // The name returns to the seller, the top bid and the compensation are owed to the bidder
+    assert_eq!(name_owner(&mut env.ctx, &accounts.name).await, seller);
+    assert_eq!(token_balance(&mut env.ctx, &seller_tokens).await, 0);
+    assert_eq!(
+        token_balance(&mut env.ctx, &fida_vault).await,
+        penalty - compensation
+    );
+    let (pot, _) = Auction::find_pot_key(&program_id, &accounts.name);
+    assert_eq!(
+        token_balance(&mut env.ctx, &pot).await,
+        top_bid + compensation
+    );
+    let instruction = withdraw_refund(
+        program_id,
+        accounts.name,
+        bidder.pubkey(),
+        bidder_tokens,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&bidder])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut env.ctx, &bidder_tokens).await,
+        top_bid + compensation
+    );
+    assert_eq!(token_balance(&mut env.ctx, &pot).await, 0);
+
+    // Once bidding is over the auction can no longer be cancelled
+    refresh_blockhash(&mut env.ctx).await;
+    sign_send_instruction(&mut env.ctx, resell_instruction, vec![])
+        .await
+        .unwrap();
+    sign_send_instruction(&mut env.ctx, bid_instruction, vec![&bidder])
+        .await
+        .unwrap();
+    let end_auction_at = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .end_auction_at;
+    warp_to_timestamp(&mut env.ctx, end_auction_at).await;
+    assert!(
+        sign_send_instruction(&mut env.ctx, cancel_instruction, vec![])
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_hidden_reserve() {
+    let mut env = setup().await;
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let seller = env.ctx.payer.pubkey();
+    let top_bid = 1_000_000_000;
+    let reserve = 2 * top_bid;
+    let salt = [3u8; 32];
+
+    // The seller accepts the top bid below its reserve within the accept window
+    let name = "accepted";
+    let accounts = name_accounts(&env, name);
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let (seller_tokens, bidder) =
+        resell_below_reserve(&mut env, name, top_bid, reserve, salt).await;
+    let instruction = reveal_reserve(
+        program_id,
+        accounts.name,
+        reselling_state,
+        seller_tokens,
+        seller,
+        central_state,
+        reserve,
+        salt,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let instruction = claim_resale(&env, name, seller_tokens, seller, bidder.pubkey());
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+    let instruction = accept_bid(
+        program_id,
+        accounts.name,
+        reselling_state,
+        seller_tokens,
+        seller,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let instruction = claim_resale(&env, name, seller_tokens, bidder.pubkey(), bidder.pubkey());
+    sign_send_instruction(&mut env.ctx, instruction, vec![&bidder])
+        .await
+        .unwrap();
+    assert_eq!(
+        name_owner(&mut env.ctx, &accounts.name).await,
+        bidder.pubkey()
+    );
+    let fee = top_bid * env.config.fees[0] / 10_000;
+    assert_eq!(
+        token_balance(&mut env.ctx, &seller_tokens).await,
+        top_bid - fee
+    );
+
+    // Once the accept window is over the seller can only reclaim the name
+    let name = "unaccepted";
+    let accounts = name_accounts(&env, name);
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let (seller_tokens, bidder) =
+        resell_below_reserve(&mut env, name, top_bid, reserve, salt).await;
+    let instruction = reveal_reserve(
+        program_id,
+        accounts.name,
+        reselling_state,
+        seller_tokens,
+        seller,
+        central_state,
+        reserve,
+        salt,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let reclaim_instruction = claim_resale(&env, name, seller_tokens, seller, bidder.pubkey());
+    assert!(
+        sign_send_instruction(&mut env.ctx, reclaim_instruction.clone(), vec![])
+            .await
+            .is_err()
+    );
+    let end_auction_at = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .end_auction_at;
+    warp_to_timestamp(
+        &mut env.ctx,
+        end_auction_at + env.config.accept_bid_window as i64,
+    )
+    .await;
+    let instruction = accept_bid(
+        program_id,
+        accounts.name,
+        reselling_state,
+        seller_tokens,
+        seller,
+        central_state,
+    );
+    assert!(sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .is_err());
+    sign_send_instruction(&mut env.ctx, reclaim_instruction, vec![])
+        .await
+        .unwrap();
+    assert_eq!(name_owner(&mut env.ctx, &accounts.name).await, seller);
+    assert_eq!(token_balance(&mut env.ctx, &seller_tokens).await, 0);
+
+    // The top bid is refunded to the bidder
+    let bidder_tokens = funded_token_account(&mut env, &TOKEN_MINT, &bidder.pubkey(), 0).await;
+    let instruction = withdraw_refund(
+        program_id,
+        accounts.name,
+        bidder.pubkey(),
+        bidder_tokens,
+        central_state,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&bidder])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut env.ctx, &bidder_tokens).await, top_bid);
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_central_state() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("name_auctioning", program_id, None);
+    program_test.add_program("spl_name_service", spl_name_service::id(), None);
+
+    // The legacy central state only stores its signer nonce
+    let (central_state, state_nonce) = CentralState::find_key(&program_id);
+    program_test.add_account(
+        central_state,
+        Account {
+            lamports: Rent::default().minimum_balance(1),
+            data: vec![state_nonce],
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut ctx = program_test.start_with_context().await;
+
+    let instruction = migrate_central_state(program_id, central_state, ctx.payer.pubkey());
+    sign_send_instruction(&mut ctx, instruction.clone(), vec![])
+        .await
+        .unwrap();
+    let account = ctx
+        .banks_client
+        .get_account(central_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), CentralState::LEN);
+    let state = CentralState::unpack_from_slice(&account.data).unwrap();
+    assert_eq!(state.signer_nonce, state_nonce);
+    assert_eq!(state.version, CENTRAL_STATE_VERSION);
+    assert_eq!(state.paused, 0);
+    assert!(state.pending_authorities.is_empty());
+    assert_eq!(state.config.auction_max_length, 259_200);
+    assert_eq!(state.config.end_auction_gap, 600);
+    assert_eq!(
+        state.config.pyth_fida_fallback_price_acc,
+        state.config.pyth_fida_price_acc
+    );
+    assert_eq!(state.config.accept_bid_window, state.config.reveal_length);
+    assert_eq!(state.config.cancellation_penalty_bps, 0);
+    state.config.check().unwrap();
+
+    // The current version cannot be migrated again
+    refresh_blockhash(&mut ctx).await;
+    assert!(sign_send_instruction(&mut ctx, instruction, vec![])
+        .await
+        .is_err());
+}
+
+// Utils
+fn test_config() -> ProgramConfig {
+    ProgramConfig {
+        bonfida_fida_vault: Pubkey::new_unique().to_bytes(),
+        bonfida_usdc_vault: Pubkey::new_unique().to_bytes(),
+        bonfida_sol_vault: Pubkey::new_unique().to_bytes(),
+        pyth_fida_price_acc: Pubkey::new_unique().to_bytes(),
+        pyth_fida_fallback_price_acc: Pubkey::new_unique().to_bytes(),
+        auction_max_length: 259200,
+        end_auction_gap: 600,
+        fees: [500, 300, 200, 150, 100],
+        fee_tiers: [10_000_000, 100_000_000, 500_000_000, 1_000_000_000],
+        authority_change_delay: 172800,
+        oracle_max_age: 25,
+        oracle_max_confidence_bps: 200,
+        referral_share_bps: 1_000,
+        bid_increment_bps: 100,
+        min_end_auction_gap: 60,
+        max_end_auction_gap: 900,
+        min_bid_increment_bps: 100,
+        max_bid_increment_bps: 1_000,
+        reveal_length: 86_400,
+        accept_bid_window: 86_400,
+        cancellation_penalty_bps: 500,
+        cancellation_compensation_bps: 5_000,
+    }
+}
+
+struct TestEnv {
+    ctx: ProgramTestContext,
+    program_id: Pubkey,
+    central_state: Pubkey,
+    config: ProgramConfig,
+    mint_authority: Keypair,
+}
+
+// Starts the program with an initialized central state owning the root domain, the payer holds
+// every admin key
+async fn setup() -> TestEnv {
+    let program_id = Pubkey::new_unique();
+    let mint_authority = Keypair::new();
+    let config = test_config();
+    let mut program_test = ProgramTest::new("name_auctioning", program_id, None);
+    program_test.add_program("spl_name_service", spl_name_service::id(), None);
+
+    program_test.add_account(TOKEN_MINT, mint_account(&mint_authority.pubkey()));
+    program_test.add_account(USDC_MINT, mint_account(&mint_authority.pubkey()));
+    let bonfida_vault_owner = Pubkey::new_unique();
+    program_test.add_account(
+        Pubkey::new(&config.bonfida_fida_vault),
+        token_account(TOKEN_MINT, bonfida_vault_owner),
+    );
+    program_test.add_account(
+        Pubkey::new(&config.bonfida_usdc_vault),
+        token_account(USDC_MINT, bonfida_vault_owner),
+    );
+    program_test.add_account(
+        Pubkey::new(&config.bonfida_sol_vault),
+        Account {
+            lamports: 1_000_000,
+            ..Account::default()
+        },
+    );
+
+    let (central_state, state_nonce) = CentralState::find_key(&program_id);
+    program_test.add_account(
+        ROOT_DOMAIN_ACCOUNT,
+        Account {
+            lamports: 1_000_000,
+            data: NameRecordHeader {
+                parent_name: Pubkey::default(),
+                owner: central_state,
+                class: Pubkey::default(),
+            }
+            .try_to_vec()
+            .unwrap(),
+            owner: spl_name_service::id(),
+            ..Account::default()
+        },
+    );
+
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+    sign_send_instruction(
+        &mut ctx,
+        init(
+            program_id,
+            central_state,
+            admin,
+            state_nonce,
+            admin,
+            admin,
+            admin,
+            config.clone(),
+        ),
+        vec![],
+    )
+    .await
+    .unwrap();
+    set_test_price(
+        &mut ctx,
+        &program_id,
+        &Pubkey::new(&config.pyth_fida_price_acc),
+        20_000_000,
+        10_000,
+        -8,
+    )
+    .await;
+
+    TestEnv {
+        ctx,
+        program_id,
+        central_state,
+        config,
+        mint_authority,
+    }
+}
+
+fn mint_account(mint_authority: &Pubkey) -> Account {
+    let mut data = vec![0u8; Mint::LEN];
+    Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply: 0,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey) -> Account {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+struct NameAccounts {
+    name: Pubkey,
+    reverse_lookup: Pubkey,
+    auction: Pubkey,
+    state: Pubkey,
+}
+
+fn name_accounts(env: &TestEnv, name: &str) -> NameAccounts {
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (name_account, _) = get_seeds_and_key(
+        &spl_name_service::id(),
+        hashed_name,
+        None,
+        Some(&ROOT_DOMAIN_ACCOUNT),
+    );
+    let hashed_reverse_lookup =
+        hashv(&[(HASH_PREFIX.to_owned() + &name_account.to_string()).as_bytes()])
+            .as_ref()
+            .to_vec();
+    let (reverse_lookup, _) = get_seeds_and_key(
+        &spl_name_service::id(),
+        hashed_reverse_lookup,
+        Some(&env.central_state),
+        None,
+    );
+    NameAccounts {
+        name: name_account,
+        reverse_lookup,
+        auction: Auction::find_key(&env.program_id, &name_account).0,
+        state: Pubkey::find_program_address(&[&name_account.to_bytes()], &env.program_id).0,
+    }
+}
+
+// Starts the primary auction of a name, paid by the payer
+fn create_auction(env: &TestEnv, name: &str, sealed_bid: bool, second_price: bool) -> Instruction {
+    let accounts = name_accounts(env, name);
+    create(
+        env.program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.reverse_lookup,
+        accounts.auction,
+        env.central_state,
+        accounts.state,
+        env.ctx.payer.pubkey(),
+        TOKEN_MINT,
+        Pubkey::new(&env.config.pyth_fida_price_acc),
+        Pubkey::new(&env.config.pyth_fida_fallback_price_acc),
+        name.to_owned(),
+        sealed_bid,
+        second_price,
+    )
+}
+
+// Registers a name directly, paid by the payer from the given token account
+fn create_v2_direct(
+    env: &TestEnv,
+    name: &str,
+    buyer_token_source: Pubkey,
+    quote_mint: Pubkey,
+    vault: Pubkey,
+    pyth_price_acc: Pubkey,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    let accounts = name_accounts(env, name);
+    create_v2(
+        env.program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.reverse_lookup,
+        env.central_state,
+        env.ctx.payer.pubkey(),
+        buyer_token_source,
+        vault,
+        accounts.state,
+        quote_mint,
+        pyth_price_acc,
+        name.to_owned(),
+        1_000,
+        vec![],
+        None,
+        referrer,
+    )
+}
+
+// Resells a name of the payer with a hidden reserve, places a bid below it and ends the auction,
+// returns the seller token account and the bidder
+async fn resell_below_reserve(
+    env: &mut TestEnv,
+    name: &str,
+    bid: u64,
+    reserve: u64,
+    salt: [u8; 32],
+) -> (Pubkey, Keypair) {
+    let program_id = env.program_id;
+    let central_state = env.central_state;
+    let seller = env.ctx.payer.pubkey();
+    let accounts = name_accounts(env, name);
+    let usdc_tokens = funded_token_account(env, &USDC_MINT, &seller, 1_000_000_000).await;
+    let instruction = create_v2_direct(
+        env,
+        name,
+        usdc_tokens,
+        USDC_MINT,
+        Pubkey::new(&env.config.bonfida_usdc_vault),
+        Pubkey::default(),
+        None,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+
+    let seller_tokens = funded_token_account(env, &TOKEN_MINT, &seller, 0).await;
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &program_id);
+    let instruction = resell(
+        program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        seller,
+        accounts.reverse_lookup,
+        accounts.auction,
+        central_state,
+        accounts.state,
+        seller,
+        reselling_state,
+        seller_tokens,
+        TOKEN_MINT,
+        name.to_owned(),
+        bid,
+        3_600,
+        None,
+        None,
+        None,
+        false,
+        Some(Auction::reserve_commitment(&accounts.name, reserve, &salt)),
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+
+    let bidder = Keypair::new();
+    let instruction = system_instruction::transfer(&seller, &bidder.pubkey(), 100_000_000);
+    sign_send_instruction(&mut env.ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let bidder_tokens = funded_token_account(env, &TOKEN_MINT, &bidder.pubkey(), bid).await;
+    let instruction = place_bid(
+        program_id,
+        accounts.name,
+        bidder.pubkey(),
+        bidder_tokens,
+        central_state,
+        None,
+        bid,
+    );
+    sign_send_instruction(&mut env.ctx, instruction, vec![&bidder])
+        .await
+        .unwrap();
+
+    let end_auction_at = auction_state(&mut env.ctx, &accounts.auction)
+        .await
+        .end_auction_at;
+    warp_to_timestamp(&mut env.ctx, end_auction_at).await;
+    (seller_tokens, bidder)
+}
+
+// Claims a resold name, paid by the payer
+fn claim_resale(
+    env: &TestEnv,
+    name: &str,
+    seller_tokens: Pubkey,
+    bidder_wallet: Pubkey,
+    top_bidder: Pubkey,
+) -> Instruction {
+    let accounts = name_accounts(env, name);
+    let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()]).to_bytes();
+    let (reselling_state, _) =
+        Pubkey::find_program_address(&[&accounts.name.to_bytes(), &[1u8, 1u8]], &env.program_id);
+    claim(
+        env.program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.auction,
+        accounts.state,
+        reselling_state,
+        env.central_state,
+        env.ctx.payer.pubkey(),
+        seller_tokens,
+        bidder_wallet,
+        1_000,
+        hashed_name,
+        Pubkey::new(&env.config.bonfida_fida_vault),
+        Pubkey::default(),
+        top_bidder,
+        None,
+    )
+}
+
+// Creates a token account of the given owner holding the given amount of tokens
+async fn funded_token_account(
+    env: &mut TestEnv,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let token_account = Keypair::new();
+    let instructions = [
+        system_instruction::create_account(
+            &env.ctx.payer.pubkey(),
+            &token_account.pubkey(),
+            Rent::default().minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        ),
+        initialize_account(&spl_token::id(), &token_account.pubkey(), mint, owner).unwrap(),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            &token_account.pubkey(),
+            &env.mint_authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&env.ctx.payer.pubkey()));
+    transaction.partial_sign(
+        &[&env.ctx.payer, &token_account, &env.mint_authority],
+        env.ctx.last_blockhash,
+    );
+    env.ctx
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    token_account.pubkey()
+}
+
+// Registers the admin set with the central state admin, which is the payer
+async fn set_admins(
+    ctx: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    admins: Vec<Pubkey>,
+    threshold: u8,
+) {
+    let instruction = set_admin_set(
+        *program_id,
+        ctx.payer.pubkey(),
+        CentralState::find_key(program_id).0,
+        ctx.payer.pubkey(),
+        None,
+        admins,
+        threshold,
+    );
+    sign_send_instruction(ctx, instruction, vec![])
+        .await
+        .unwrap();
+}
+
+// Proposes an action from the payer and approves it with the given admins
+async fn propose(
+    ctx: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    index: u64,
+    action: AdminAction,
+    approvers: Vec<&Keypair>,
+) -> Pubkey {
+    let instruction = create_proposal(
+        *program_id,
+        ctx.payer.pubkey(),
+        ctx.payer.pubkey(),
+        index,
+        action,
+    );
+    sign_send_instruction(ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let (proposal, _) = Proposal::find_key(program_id, index);
+    for approver in approvers {
+        sign_send_instruction(
+            ctx,
+            approve_proposal(*program_id, approver.pubkey(), proposal),
+            vec![approver],
+        )
+        .await
+        .unwrap();
+    }
+    proposal
+}
+
+// Identical transactions are only processed once per blockhash
+async fn refresh_blockhash(ctx: &mut ProgramTestContext) {
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+}
+
+async fn token_balance(ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = ctx
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+async fn auction_state(ctx: &mut ProgramTestContext, auction: &Pubkey) -> Auction {
+    let account = ctx
+        .banks_client
+        .get_account(*auction)
+        .await
+        .unwrap()
+        .unwrap();
+    Auction::unpack_from_slice(&account.data).unwrap()
+}
+
+async fn registration_expiry(env: &mut TestEnv, name_account: &Pubkey) -> i64 {
+    let (registration, _) = Registration::find_key(&env.program_id, name_account);
+    let account = env
+        .ctx
+        .banks_client
+        .get_account(registration)
+        .await
+        .unwrap()
+        .unwrap();
+    Registration::unpack_from_slice(&account.data)
+        .unwrap()
+        .expires_at
+}
+
+async fn name_owner(ctx: &mut ProgramTestContext, name_account: &Pubkey) -> Pubkey {
+    let account = ctx
+        .banks_client
+        .get_account(*name_account)
+        .await
+        .unwrap()
+        .unwrap();
+    NameRecordHeader::unpack_from_slice(&account.data)
+        .unwrap()
+        .owner
+}
+
+// Moves the clock forward, the blockhash is refreshed so that identical transactions can be resent
+async fn warp_to_timestamp(ctx: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    ctx.set_sysvar(&clock);
+    refresh_blockhash(ctx).await;
+}
+
+// Registers a name directly with a voucher signed by the admin, which is the payer
+fn voucher_instructions(
+    env: &TestEnv,
+    name: &str,
+    voucher: &Voucher,
+    buyer_token_source: Pubkey,
+) -> Vec<Instruction> {
+    let accounts = name_accounts(env, name);
+    let create_instruction = create_v2(
+        env.program_id,
+        ROOT_DOMAIN_ACCOUNT,
+        accounts.name,
+        accounts.reverse_lookup,
+        env.central_state,
+        env.ctx.payer.pubkey(),
+        buyer_token_source,
+        Pubkey::new(&env.config.bonfida_usdc_vault),
+        accounts.state,
+        USDC_MINT,
+        Pubkey::default(),
+        name.to_owned(),
+        1_000,
+        vec![],
+        Some(voucher.clone()),
+        None,
+    );
+    vec![
+        ed25519_instruction(&env.ctx.payer, &voucher.try_to_vec().unwrap()),
+        create_instruction,
+    ]
+}
+
+// Ed25519 program instruction verifying a single signature contained in its own data
+fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let (pubkey_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+    let mut data = vec![1, 0];
+    for v in [
+        signature_offset,
+        u16::MAX,
+        pubkey_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&v.to_le_bytes());
+    }
+    data.extend_from_slice(&signer.pubkey().to_bytes());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+pub async fn sign_send_instruction(
+    ctx: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: Vec<&Keypair>,
+) -> Result<(), BanksClientError> {
+    sign_send_instructions(ctx, vec![instruction], signers).await
+}
+
+pub async fn sign_send_instructions(
+    ctx: &mut ProgramTestContext,
+    instructions: Vec<Instruction>,
+    signers: Vec<&Keypair>,
+) -> Result<(), BanksClientError> {
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&ctx.payer.pubkey()));
+    let mut payer_signers = vec![&ctx.payer];
+    for s in signers {
+        payer_signers.push(s);
+    }
+    transaction.partial_sign(&payer_signers, ctx.last_blockhash);
+    ctx.banks_client.process_transaction(transaction).await
+}
+
+// Writes a FIDA price published at the current slot, worth price * 10^expo USD
+async fn set_test_price(
+    ctx: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    price_account: &Pubkey,
+    price: i64,
+    conf: u64,
+    expo: i32,
+) {
+    let slot = ctx.banks_client.get_root_slot().await.unwrap();
+    let mut data = vec![0u8; TestPrice::LEN];
+    TestPrice::new(price, conf, expo, slot).pack_into_slice(&mut data);
+    ctx.set_account(
+        price_account,
+        &Account {
+            lamports: 1_000_000,
+            data,
+            owner: *program_id,
+            ..Account::default()
+        }
+        .into(),
+    );
 }
 
 async fn create_token_account(